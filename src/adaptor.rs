@@ -4,6 +4,8 @@ use source::Source;
 
 use crate::{
     Error,
+    Map,
+    MergeStrategy,
     Result,
     Value,
 };
@@ -15,10 +17,18 @@ pub mod source;
 #[derive(Debug, Clone)]
 pub struct Adaptor {
     /// The underlying source of configuration data.
-    source:       Arc<dyn Source<Error = Error, Value = Value>>,
-    pub priority: u8,
-    pub watch:    bool,
-    pub profile:  Option<String>,
+    source:             Arc<dyn Source<Error = Error, Value = Value>>,
+    pub priority:       u8,
+    pub watch:          bool,
+    pub profile:        Option<String>,
+    /// How arrays from this adaptor are merged into earlier layers.
+    pub merge_strategy: Option<MergeStrategy>,
+    /// Key path the source's output is nested under.
+    pub mount:          Option<String>,
+    /// Whether every value from this adaptor is redacted when shown.
+    pub sensitive:      bool,
+    /// Whether a missing file is skipped instead of failing the build.
+    pub optional:       bool,
 }
 
 impl Adaptor {
@@ -27,21 +37,41 @@ impl Adaptor {
         source: T,
     ) -> Self {
         Self {
-            source:   Arc::new(source),
-            priority: 0,
-            watch:    false,
-            profile:  None,
+            source:         Arc::new(source),
+            priority:       0,
+            watch:          false,
+            profile:        None,
+            merge_strategy: None,
+            mount:          None,
+            sensitive:      false,
+            optional:       false,
         }
     }
 
     /// Parses the configuration data from the source.
     ///
+    /// If the adaptor is mounted, the parsed value is nested under the mount
+    /// path.
+    ///
     /// # Returns
     ///
     /// Returns a `Result` containing either the parsed `Value` or a
     /// `Error`.
     pub fn parse(&self) -> Result<Value> {
-        self.source.parse()
+        let value = self.source.parse()?;
+        match &self.mount {
+            Some(path) if value != Value::Null => {
+                let mut mounted = Value::Table(Map::new());
+                mounted.set(path.as_str(), value)?;
+                Ok(mounted)
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Describes the underlying source, e.g. `file config.toml`.
+    pub fn describe(&self) -> String {
+        self.source.describe()
     }
 
     /// Set the priority of the adaptor.
@@ -70,6 +100,66 @@ impl Adaptor {
         self
     }
 
+    /// Nest the whole output of the source under the key path `path` before
+    /// it is merged, e.g. `services.payments`.
+    ///
+    /// # Returns
+    ///
+    /// Returns the adaptor with the mount path set.
+    #[must_use]
+    pub fn mount(mut self, path: impl Into<String>) -> Self {
+        self.mount = Some(path.into());
+        self
+    }
+
+    /// Set how arrays from this adaptor are merged into the layers loaded
+    /// before it.
+    ///
+    /// Strategies set for a key path with `RealmeBuilder::merge_strategy`
+    /// take precedence over this one. Without either, arrays are replaced.
+    ///
+    /// # Returns
+    ///
+    /// Returns the adaptor with the merge strategy set.
+    #[must_use]
+    pub fn merge_strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.merge_strategy = Some(strategy);
+        self
+    }
+
+    /// Mark every value from this adaptor as sensitive, e.g. a secrets file
+    /// or a vault source.
+    ///
+    /// Sensitive values are redacted in `Realme`'s `Debug` output, in its
+    /// layers and in errors about them.
+    ///
+    /// # Returns
+    ///
+    /// Returns the adaptor marked as sensitive.
+    #[must_use]
+    pub const fn sensitive(mut self) -> Self {
+        self.sensitive = true;
+        self
+    }
+
+    /// Mark the adaptor as optional, e.g. for a local override file that
+    /// may not exist.
+    ///
+    /// If the source's file is missing, the adaptor is skipped instead of
+    /// failing the build, and a [`Warning::SkippedSource`] is reported. Other
+    /// errors, such as a syntax error, still fail the build.
+    ///
+    /// [`Warning::SkippedSource`]: crate::Warning::SkippedSource
+    ///
+    /// # Returns
+    ///
+    /// Returns the adaptor marked as optional.
+    #[must_use]
+    pub const fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+
     /// Set the watch for the adaptor.
     ///
     /// With watch set, the adaptor will watch the source file and reload the
@@ -16,8 +16,18 @@ pub mod toml;
 #[cfg(feature = "yaml")]
 pub mod yaml;
 
+use std::{
+    any::Any,
+    fmt::Display,
+};
+
 use serde::Serialize;
 
+use crate::{
+    Error,
+    Value,
+};
+
 /// A trait for parsing input of type `T` into a serializable item.
 ///
 /// This trait defines a generic parser that can convert input of type `T`
@@ -46,4 +56,43 @@ pub trait Parser<T> {
     /// A `Result` containing either the successfully parsed `Item` or an
     /// `Error`.
     fn parse(args: T) -> Result<Self::Item, Self::Error>;
+
+    /// Parses the input arguments straight into a `Value`.
+    ///
+    /// By default the item returned by `parse` is serialized into a `Value`.
+    /// Parsers that can build the `Value` while reading the input override it
+    /// to skip that second pass.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `parse`, turned into an `Error`, or the error of
+    /// serializing the item.
+    fn parse_value(args: T) -> Result<Value, Error>
+    where
+        Self::Error: 'static,
+    {
+        let item = Self::parse(args).map_err(into_error)?;
+        Value::try_serialize(&item)
+    }
+}
+
+/// Turns the error of a parser into an `Error`.
+///
+/// The built-in parsers fail with an `Error`, which keeps its variant and
+/// location. The `Parser` trait only asks for a `Display` error though, so any
+/// other error becomes a `ParseError` with its message as the cause. Sources
+/// then name themselves as its origin with [`Error::in_source`].
+fn into_error<E>(error: E) -> Error
+where
+    E: Display + 'static,
+{
+    let mut error = Some(error);
+    if let Some(error) = (&mut error as &mut dyn Any)
+        .downcast_mut::<Option<Error>>()
+        .and_then(Option::take)
+    {
+        return error;
+    }
+    let cause = error.map(|e| e.to_string()).unwrap_or_default();
+    Error::new_parse_error(String::new(), cause)
 }
@@ -73,14 +73,14 @@ impl CmdParser {
 
         if key.is_empty() {
             return Err(Error::new_parse_error(
-                pair.to_string(),
+                "cmd".to_string(),
                 "key can not be empty".to_string(),
             ));
         }
 
         if value.is_empty() {
             return Err(Error::new_parse_error(
-                pair.to_string(),
+                "cmd".to_string(),
                 format!("key : {key} has empty value"),
             ));
         }
@@ -120,8 +120,12 @@ impl CmdParser {
         }
 
         // Try to parse as number
-        if let Ok(int_val) = trimmed.parse::<i64>() {
-            return Value::Integer(int_val);
+        if let Ok(int_val) = trimmed.parse::<i128>() {
+            return Value::from(int_val);
+        }
+
+        if let Ok(uint_val) = trimmed.parse::<u128>() {
+            return Value::from(uint_val);
         }
 
         if let Ok(float_val) = trimmed.parse::<f64>() {
@@ -328,6 +332,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_large_integer() -> Result<(), Error> {
+        let result = CmdParser::parse(
+            "mask=18446744073709551615, \
+             big=-170141183460469231731687303715884105728",
+        )?;
+        let expected = Value::Table(Map::from_iter([
+            ("mask".to_string(), Value::UInteger(u64::MAX)),
+            ("big".to_string(), Value::Integer128(i128::MIN)),
+        ]));
+        assert_eq!(result, expected);
+        Ok(())
+    }
+
     #[test]
     #[allow(clippy::approx_constant)]
     fn test_parse_float() -> Result<(), Error> {
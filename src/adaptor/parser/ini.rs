@@ -1,6 +1,7 @@
 use crate::{
     Error,
     Map,
+    errors::ParseError,
     prelude::*,
 };
 /// A parser for INI format configuration files.
@@ -47,9 +48,12 @@ impl<T: AsRef<str>> Parser<T> for IniParser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
+        let args = args.as_ref().trim_end();
         let i = ini::Ini::load_from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
+            Error::ParseError(
+                ParseError::new("ini".to_string(), e.msg.to_string())
+                    .at_line(args, e.line, e.col),
+            )
         })?;
         let mut map = Map::new();
         for (sec, prop) in &i {
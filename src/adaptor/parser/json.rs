@@ -4,6 +4,7 @@
 /// `serde_json::Value`.
 use crate::{
     Error,
+    errors::ParseError,
     prelude::*,
 };
 
@@ -32,9 +33,25 @@ impl<T: AsRef<str>> Parser<T> for JsonParser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
+        let args = args.as_ref().trim_end();
         serde_json::from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
+            if e.line() == 0 {
+                return Error::new_parse_error(
+                    "json".to_string(),
+                    e.to_string(),
+                );
+            }
+            // The message ends with the position, which is reported apart.
+            let message = e.to_string();
+            let suffix = format!(" at line {} column {}", e.line(), e.column());
+            let cause = message.strip_suffix(&suffix).unwrap_or(&message);
+            Error::ParseError(
+                ParseError::new("json".to_string(), cause.to_string()).at_line(
+                    args,
+                    e.line(),
+                    e.column(),
+                ),
+            )
         })
     }
 }
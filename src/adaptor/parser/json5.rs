@@ -4,6 +4,7 @@
 /// into `Value` objects.
 use crate::{
     Error,
+    errors::ParseError,
     prelude::*,
 };
 
@@ -33,9 +34,18 @@ impl<T: AsRef<str>> Parser<T> for Json5Parser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
+        let args = args.as_ref().trim_end();
         serde_json5::from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
+            let serde_json5::Error::Message { msg, location } = e;
+            // Syntax errors render the offending line; keep only the
+            // message after it.
+            let cause =
+                msg.rsplit_once("\n  = ").map_or(msg.as_str(), |(_, m)| m);
+            let error = ParseError::new("json5".to_string(), cause.to_string());
+            Error::ParseError(match location {
+                Some(l) => error.at_line(args, l.line, l.column),
+                None => error,
+            })
         })
     }
 }
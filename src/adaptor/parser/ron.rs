@@ -4,6 +4,7 @@
 /// strings.
 use crate::{
     Error,
+    errors::ParseError,
     prelude::*,
 };
 
@@ -33,10 +34,14 @@ impl<T: AsRef<str>> Parser<T> for RonParser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
-        let v = ron::from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
-        })?;
+        let args = args.as_ref().trim_end();
+        let v =
+            ron::from_str(args).map_err(|e| {
+                Error::ParseError(
+                    ParseError::new("ron".to_string(), e.code.to_string())
+                        .at_line(args, e.span.start.line, e.span.start.col),
+                )
+            })?;
         Ok(v)
     }
 }
@@ -4,6 +4,7 @@
 /// `toml::Value` objects.
 use crate::{
     Error,
+    errors::ParseError,
     prelude::*,
 };
 
@@ -33,9 +34,14 @@ impl<T: AsRef<str>> Parser<T> for TomlParser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
+        let args = args.as_ref().trim_end();
         toml::from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
+            let error =
+                ParseError::new("toml".to_string(), e.message().to_string());
+            Error::ParseError(match e.span() {
+                Some(span) => error.at(args, span.start),
+                None => error,
+            })
         })
     }
 }
@@ -1,9 +1,31 @@
 /// A parser for YAML format.
 ///
 /// This struct implements the `Parser` trait for YAML parsing.
+use std::collections::HashMap;
+
+use yaml_rust2::{
+    Event,
+    Yaml,
+    parser::{
+        MarkedEventReceiver,
+        Parser as EventParser,
+        Tag,
+    },
+    scanner::{
+        Marker,
+        TScalarStyle,
+    },
+};
+
 use crate::{
     Error,
+    errors::ParseError,
     prelude::*,
+    value::{
+        Array,
+        Table,
+        parse_datetime,
+    },
 };
 
 #[derive(Debug)]
@@ -32,9 +54,197 @@ impl<T: AsRef<str>> Parser<T> for YamlParser {
     /// assert!(result.is_ok());
     /// ```
     fn parse(args: T) -> Result<Self::Item, Self::Error> {
-        let args = args.as_ref().trim();
+        let args = args.as_ref().trim_end();
         serde_yaml2::from_str(args).map_err(|e| {
-            Error::new_parse_error(args.to_string(), e.to_string())
+            let (cause, position) = split_position(&e.to_string());
+            let error = ParseError::new("yaml".to_string(), cause);
+            Error::ParseError(match position {
+                Some((line, column)) => error.at_line(args, line, column),
+                None => error,
+            })
         })
     }
+
+    /// Parses a YAML string into a `Value` in a single pass over its events.
+    ///
+    /// Plain scalars holding an RFC 3339 timestamp with a date, such as
+    /// `2001-12-14T21:59:43.10-05:00` or `2002-12-14`, become
+    /// `Value::Datetime`. Quoted or tagged scalars stay strings.
+    fn parse_value(args: T) -> Result<Value, Error> {
+        let args = args.as_ref().trim_end();
+        let mut builder = ValueBuilder::default();
+        EventParser::new_from_str(args)
+            .load(&mut builder, false)
+            .map_err(|e| (e.info().to_string(), *e.marker()))
+            .and(builder.error.map_or(Ok(()), Err))
+            .map_err(|(cause, marker)| {
+                Error::ParseError(
+                    ParseError::new("yaml".to_string(), cause).at_line(
+                        args,
+                        marker.line(),
+                        marker.col() + 1,
+                    ),
+                )
+            })?;
+        Ok(builder.root.unwrap_or_default())
+    }
+}
+
+/// Splits the position out of a `serde_yaml2` error message, which only
+/// mentions it as ` at position Line: 3, Column: 4, Index: 20`. Columns there
+/// are zero-based.
+fn split_position(message: &str) -> (String, Option<(usize, usize)>) {
+    let Some(start) = message.find(" at position Line: ") else {
+        return (message.to_string(), None);
+    };
+    let mut numbers = Vec::new();
+    let mut end = start;
+    for label in ["Line: ", "Column: ", "Index: "] {
+        let Some(at) = message[end..].find(label) else {
+            return (message.to_string(), None);
+        };
+        let digits = end + at + label.len();
+        end = message[digits..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(message.len(), |i| digits + i);
+        numbers.push(message[digits..end].parse().unwrap_or_default());
+    }
+    let cause = format!("{}{}", &message[..start], &message[end..]);
+    (cause, Some((numbers[0], numbers[1] + 1)))
+}
+
+/// Builds a `Value` from the events of the first YAML document.
+#[derive(Default)]
+struct ValueBuilder {
+    /// The collections around the current node, with their anchor ids.
+    stack:   Vec<(Node, usize)>,
+    anchors: HashMap<usize, Value>,
+    root:    Option<Value>,
+    /// The first error found, since events cannot fail.
+    error:   Option<(String, Marker)>,
+}
+
+/// A collection being read.
+enum Node {
+    Sequence(Array),
+    Mapping { table: Table, key: Option<String> },
+}
+
+impl ValueBuilder {
+    /// Adds a finished node to its collection, or makes it the root.
+    fn insert(&mut self, value: Value, anchor: usize, marker: Marker) {
+        if anchor > 0 {
+            self.anchors.insert(anchor, value.clone());
+        }
+        match self.stack.last_mut() {
+            None => self.root = Some(value),
+            Some((Node::Sequence(array), _)) => array.push(value),
+            Some((Node::Mapping { table, key }, _)) => {
+                match (key.take(), value) {
+                    (Some(key), value) => {
+                        table.insert(key, value);
+                    }
+                    (None, Value::String(text)) => *key = Some(text),
+                    (None, _) => {
+                        self.fail("only scalar keys are supported", marker);
+                    }
+                }
+            }
+        }
+    }
+
+    fn fail(&mut self, cause: &str, marker: Marker) {
+        self.error.get_or_insert((cause.to_string(), marker));
+    }
+}
+
+impl MarkedEventReceiver for ValueBuilder {
+    fn on_event(&mut self, event: Event, marker: Marker) {
+        match event {
+            Event::Scalar(text, style, anchor, tag) => {
+                // Keys are kept as written, whatever they would resolve to.
+                let is_key = matches!(
+                    self.stack.last(),
+                    Some((Node::Mapping { key: None, .. }, _))
+                );
+                let value = if is_key {
+                    Some(Value::String(text))
+                } else {
+                    resolve_scalar(text, style, tag)
+                };
+                match value {
+                    Some(value) => self.insert(value, anchor, marker),
+                    None => self.fail("invalid value for its tag", marker),
+                }
+            }
+            Event::Alias(anchor) => match self.anchors.get(&anchor) {
+                Some(value) => self.insert(value.clone(), 0, marker),
+                None => self.fail("unknown anchor", marker),
+            },
+            Event::SequenceStart(anchor, _) => {
+                self.stack.push((Node::Sequence(Array::new()), anchor));
+            }
+            Event::MappingStart(anchor, _) => {
+                self.stack.push((
+                    Node::Mapping {
+                        table: Table::new(),
+                        key:   None,
+                    },
+                    anchor,
+                ));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((node, anchor)) = self.stack.pop() {
+                    let value = match node {
+                        Node::Sequence(array) => Value::Array(array),
+                        Node::Mapping { table, .. } => Value::Table(table),
+                    };
+                    self.insert(value, anchor, marker);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Resolves a scalar the way `yaml_rust2` does, except that plain timestamps
+/// become `Value::Datetime`. Returns `None` when the scalar does not match its
+/// `!!bool`, `!!int`, `!!float` or `!!null` tag.
+fn resolve_scalar(
+    text: String,
+    style: TScalarStyle,
+    tag: Option<Tag>,
+) -> Option<Value> {
+    if style != TScalarStyle::Plain {
+        return Some(Value::String(text));
+    }
+    if let Some(Tag { handle, suffix }) = tag {
+        if handle != "tag:yaml.org,2002:" {
+            return Some(Value::String(text));
+        }
+        return match suffix.as_str() {
+            "bool" => text.parse().ok().map(Value::Boolean),
+            "int" => text.parse().ok().map(Value::Integer),
+            "float" => Yaml::Real(text).as_f64().map(Value::Float),
+            "null" => {
+                matches!(text.as_str(), "~" | "null").then_some(Value::Null)
+            }
+            _ => Some(Value::String(text)),
+        };
+    }
+    if let Some(datetime) = parse_datetime(&text) &&
+        datetime.date.is_some()
+    {
+        return Some(Value::Datetime(datetime));
+    }
+    let yaml = Yaml::from_str(&text);
+    Some(match yaml {
+        Yaml::Null => Value::Null,
+        Yaml::Boolean(b) => Value::Boolean(b),
+        Yaml::Integer(i) => Value::Integer(i),
+        Yaml::Real(_) => {
+            yaml.as_f64().map_or(Value::String(text), Value::Float)
+        }
+        _ => Value::String(text),
+    })
 }
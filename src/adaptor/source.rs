@@ -26,6 +26,12 @@ pub trait Source: Send + Sync + Debug {
     /// - `Err(Error)` if an error occurs during parsing
     fn parse(&self) -> Result<Self::Value, Self::Error>;
 
+    /// A short human-readable description of the source, e.g. the file path
+    /// it reads from
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+
     #[cfg(feature = "watch")]
     /// Watch the source for changes
     fn watcher(
@@ -33,15 +33,17 @@ source_debug!(CmdSource<T>);
 impl<T> Source for CmdSource<T>
 where
     T: for<'a> Parser<&'a str> + Send + Sync,
+    for<'a> <T as Parser<&'a str>>::Error: 'static,
 {
     type Error = Error;
     type Value = Value;
     fn parse(&self) -> Result<Value, Self::Error> {
-        T::parse(&self.options)
-            .map_err(|e| {
-                Error::new_parse_error(self.options.clone(), e.to_string())
-            })
-            .and_then(|v| Value::try_serialize(&v))
+        T::parse_value(&self.options).map_err(|e| e.in_source(self.describe()))
+    }
+
+    /// The options may hold secrets, so they are left out.
+    fn describe(&self) -> String {
+        "command line".to_string()
     }
 
     #[cfg(feature = "watch")]
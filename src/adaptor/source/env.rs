@@ -32,15 +32,16 @@ impl<T> EnvSource<T> {
 impl<T> Source for EnvSource<T>
 where
     T: for<'a> Parser<&'a str> + Send + Sync,
+    for<'a> <T as Parser<&'a str>>::Error: 'static,
 {
     type Error = Error;
     type Value = Value;
     fn parse(&self) -> Result<Value, Error> {
-        T::parse(&self.prefix)
-            .map_err(|e| {
-                Error::new_parse_error(self.prefix.clone(), e.to_string())
-            })
-            .and_then(|v| Value::try_serialize(&v))
+        T::parse_value(&self.prefix).map_err(|e| e.in_source(self.describe()))
+    }
+
+    fn describe(&self) -> String {
+        format!("env {}", self.prefix)
     }
 
     #[cfg(feature = "watch")]
@@ -44,14 +44,18 @@ impl<T> FileSource<T> {
     }
 
     fn get_buffer(&self) -> Result<String> {
-        let buffer =
-            std::fs::read_to_string(self.path.clone()).map_err(|e| {
-                Error::ReadFileError(format!(
-                    "Failed to read file: {}, error: {}",
-                    self.path.display(),
-                    e
-                ))
-            })?;
+        let buffer = std::fs::read_to_string(&self.path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                Error::FileNotFound {
+                    path: self.path.clone(),
+                }
+            } else {
+                Error::ReadFileError {
+                    path:   self.path.clone(),
+                    source: e,
+                }
+            }
+        })?;
 
         #[cfg(feature = "placeholder")]
         {
@@ -62,28 +66,20 @@ impl<T> FileSource<T> {
 
             use crate::utils::get_env;
 
+            let template_error = |source| Error::TemplateError {
+                path: self.path.clone(),
+                source,
+            };
             let mut env = Environment::new();
             env.add_function("env", get_env);
-            env.add_template("config", &buffer).map_err(|e| {
-                Error::new_parse_error(
-                    self.path.display().to_string(),
-                    format!("Failed to add template: {e}"),
-                )
-            })?;
+            env.add_template("config", &buffer)
+                .map_err(template_error)?;
 
-            let template = env.get_template("config").map_err(|e| {
-                Error::new_parse_error(
-                    self.path.display().to_string(),
-                    format!("Failed to get template: {e}"),
-                )
-            })?;
+            let template =
+                env.get_template("config").map_err(template_error)?;
 
-            let rendered = template.render(context!()).map_err(|e| {
-                Error::new_parse_error(
-                    self.path.display().to_string(),
-                    format!("Failed to render template: {e}"),
-                )
-            })?;
+            let rendered =
+                template.render(context!()).map_err(template_error)?;
             Ok(rendered)
         }
         #[cfg(not(feature = "placeholder"))]
@@ -96,6 +92,7 @@ impl<T> FileSource<T> {
 impl<T> Source for FileSource<T>
 where
     T: for<'a> Parser<&'a str> + Send + Sync,
+    for<'a> <T as Parser<&'a str>>::Error: 'static,
 {
     type Error = Error;
     type Value = Value;
@@ -104,14 +101,11 @@ where
         let buffer = self.get_buffer()?;
 
         // Parse the rendered content
-        T::parse(&buffer)
-            .map_err(|e| {
-                Error::new_parse_error(
-                    self.path.display().to_string(),
-                    e.to_string(),
-                )
-            })
-            .and_then(|v| Value::try_serialize(&v))
+        T::parse_value(&buffer).map_err(|e| e.in_source(self.describe()))
+    }
+
+    fn describe(&self) -> String {
+        format!("file {}", self.path.display())
     }
 
     #[cfg(feature = "watch")]
@@ -138,7 +132,10 @@ where
             .map_err(|e| {
                 #[cfg(feature = "tracing")]
                 tracing::error!("Watcher error: {:?}", e);
-                Error::WatcherError(e.to_string())
+                Error::WatcherError {
+                    path:   path.to_path_buf(),
+                    source: Box::new(e),
+                }
             })?;
 
             notify::Watcher::watch(
@@ -149,7 +146,10 @@ where
             .map_err(|e| {
                 #[cfg(feature = "tracing")]
                 tracing::error!("Watcher error: {:?}", e);
-                Error::WatcherError(e.to_string())
+                Error::WatcherError {
+                    path:   path.to_path_buf(),
+                    source: Box::new(e),
+                }
             })?;
 
             while let Ok(_event) = rx.recv() {
@@ -1,4 +1,10 @@
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    },
+};
 
 use crate::{
     Error,
@@ -6,6 +12,10 @@ use crate::{
     source_debug,
 };
 
+/// Numbers inline strings, so errors can say which one failed to parse
+/// without quoting it.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
 /// A `Source` implementation that reads from a string buffer.
 ///
 /// This struct holds a reference to a string buffer and parses it using a
@@ -13,6 +23,7 @@ use crate::{
 /// type of the buffer which must implement `AsRef<str>` and `Clone`.
 pub struct StringSource<T> {
     buffer:  String,
+    id:      usize,
     _marker: PhantomData<T>,
 }
 
@@ -26,6 +37,7 @@ impl<T> StringSource<T> {
     pub fn new<U: Into<String>>(buffer: U) -> Self {
         Self {
             buffer:  buffer.into(),
+            id:      NEXT_ID.fetch_add(1, Ordering::Relaxed),
             _marker: PhantomData,
         }
     }
@@ -34,15 +46,16 @@ impl<T> StringSource<T> {
 impl<T> Source for StringSource<T>
 where
     T: for<'a> Parser<&'a str> + Send + Sync,
+    for<'a> <T as Parser<&'a str>>::Error: 'static,
 {
     type Error = Error;
     type Value = Value;
     fn parse(&self) -> Result<Value, Error> {
-        T::parse(&self.buffer)
-            .map_err(|e| {
-                Error::new_parse_error(self.buffer.clone(), e.to_string())
-            })
-            .and_then(|v| Value::try_serialize(&v))
+        T::parse_value(&self.buffer).map_err(|e| e.in_source(self.describe()))
+    }
+
+    fn describe(&self) -> String {
+        format!("inline string #{}", self.id)
     }
 
     #[cfg(feature = "watch")]
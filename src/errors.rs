@@ -1,40 +1,109 @@
 //! Error types for the crate
 
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::PathBuf,
+};
 
 use thiserror::Error;
 
+use crate::{
+    MergeConflict,
+    Value,
+};
+
 /// The error type for this crate
+///
+/// Variants carry the key, path or source they are about in public fields, so
+/// callers can tell errors apart without matching on messages. Errors caused
+/// by another one, such as an adaptor failing to load, expose it through
+/// `source()`.
 #[derive(Error, Debug)]
 pub enum Error {
     #[error(transparent)]
     InvalidCast(CastError),
     #[error(transparent)]
     ParseError(ParseError),
-    #[error("Expression error: {0}")]
-    ExprError(String),
-
-    #[error("Set value error: {0}")]
-    SetValueError(String),
-
-    #[error("Build error: {0}")]
-    BuildError(String),
-    #[error("Read file error: {0}")]
-    ReadFileError(String),
-    #[error("Tera error: {0}")]
-    TeraError(String),
-    #[error("Watcher error: {0}")]
-    WatcherError(String),
-    #[error("Lock error: {0}")]
-    LockError(String),
+    #[error("Invalid expression `{expr}`: {source}")]
+    ExprError { expr: String, source: SyntaxError },
+    #[error("Invalid key `{key}`: {source}")]
+    InvalidKey { key: String, source: Box<Self> },
+    #[error("Missing key `{0}`")]
+    MissingKey(String),
+    #[error("Invalid value at `{key}`: {source}")]
+    InvalidValue { key: String, source: Box<Self> },
+    /// Stands in for an error that would have shown a sensitive value.
+    #[error("the value is sensitive and was redacted")]
+    Redacted,
+    #[error("Invalid duration: {0}")]
+    InvalidDuration(#[source] QuantityError),
+    #[error("Invalid byte size: {0}")]
+    InvalidByteSize(#[source] QuantityError),
+
+    #[error("Cannot set `{key}`: {source}")]
+    SetValueError { key: String, source: AccessError },
+    #[error("Cannot patch `{path}`: {source}")]
+    PatchError { path: String, source: AccessError },
+
+    #[error(
+        "Profile `{name}` not found, available profiles: [{}]",
+        .available.join(", ")
+    )]
+    ProfileNotFound {
+        name:      String,
+        available: Vec<String>,
+    },
+    #[error("Failed to load {adaptor}: {source}")]
+    AdaptorFailed { adaptor: String, source: Box<Self> },
+    #[error("Expected {adaptor} to produce a table, got {found}")]
+    NotATable {
+        adaptor: String,
+        found:   &'static str,
+    },
+    #[error("Type conflicts while merging: {}", join(.0, ", "))]
+    TypeConflicts(Vec<MergeConflict>),
+    #[error(
+        "Both `{old}` and `{new}` are set with different values, `{old}` is \
+         deprecated in favour of `{new}`"
+    )]
+    AliasConflict { old: String, new: String },
+    #[error("Invalid sensitive key `{key}`: {source}")]
+    InvalidSensitiveKey { key: String, source: Box<Self> },
+    #[error("{} errors while building: {}", .0.len(), join(.0, "; "))]
+    BuildErrors(Vec<Self>),
+
+    #[error("File not found: {}", .path.display())]
+    FileNotFound { path: PathBuf },
+    #[error("Failed to read file {}: {source}", .path.display())]
+    ReadFileError {
+        path:   PathBuf,
+        source: std::io::Error,
+    },
+    #[cfg(feature = "placeholder")]
+    #[error("Failed to render template {}: {source}", .path.display())]
+    TemplateError {
+        path:   PathBuf,
+        source: minijinja::Error,
+    },
+    #[error("Failed to watch {}: {source}", .path.display())]
+    WatcherError {
+        path:   PathBuf,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 
     #[error(transparent)]
     DeserializeError(DeserializeError),
     #[error(transparent)]
     SerializeError(SerializeError),
+}
 
-    #[error("Unknown error: {0}")]
-    Unknown(String),
+/// Joins the items with `separator` for error messages.
+fn join<T: Display>(items: &[T], separator: &str) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
 }
 
 /// Convenience type alias for this crate's error type
@@ -51,20 +120,104 @@ impl Error {
         Self::ParseError(ParseError::new(origin, cause))
     }
 
-    /// Creates a new `BuildError`.
-    #[allow(clippy::missing_const_for_fn)]
-    pub fn new_build_error(cause: String) -> Self {
-        #[cfg(feature = "tracing")]
-        tracing::error!("Build error: {}", cause);
-        Self::BuildError(cause)
+    /// Attributes an error raised while parsing a source to that source.
+    ///
+    /// A `ParseError` keeps its cause and location and takes `origin`. Other
+    /// errors are returned as is, since they don't point into the source;
+    /// the builder still names the adaptor they came from.
+    #[must_use]
+    pub fn in_source<O: Into<String>>(self, origin: O) -> Self {
+        match self {
+            Self::ParseError(e) => Self::ParseError(e.with_origin(origin)),
+            other => other,
+        }
     }
+
+    /// Drops the snippet of a parse error, see
+    /// [`ParseError::without_snippet`]. Other errors are returned as is.
+    #[must_use]
+    pub fn without_snippet(self) -> Self {
+        match self {
+            Self::ParseError(e) => Self::ParseError(e.without_snippet()),
+            other => other,
+        }
+    }
+}
+
+/// Why a key expression or query failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SyntaxError {
+    #[error("empty expression")]
+    Empty,
+    #[error("unexpected `{0}`")]
+    Unexpected(char),
+    #[error("unmatched `{0}`")]
+    Unmatched(char),
+    #[error("unterminated quote")]
+    UnterminatedQuote,
+    #[error("invalid escape in a quoted key")]
+    InvalidEscape,
+    #[error("expected `.` or `[` after a quoted key")]
+    ExpectedSeparator,
+    #[error("subscript without a key")]
+    SubscriptWithoutKey,
+    #[error("invalid subscript `[{0}]`")]
+    InvalidSubscript(String),
+    #[error("expected a key after `..`")]
+    ExpectedKey,
+}
+
+/// Why a value could not be set or patched at a path.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AccessError {
+    /// A value on the path has another type than the step needs.
+    #[error("expected {expected}, got {found}")]
+    TypeMismatch {
+        expected: &'static str,
+        found:    &'static str,
+    },
+    #[error("invalid array index `{0}`")]
+    InvalidIndex(String),
+    #[error("index {index} is out of bounds for length {len}")]
+    OutOfBounds { index: isize, len: usize },
+    #[error("path not found")]
+    NotFound,
+    #[error("parent not found")]
+    ParentNotFound,
+    #[error("cannot move `{0}` into its own child")]
+    MoveIntoChild(String),
+    #[error("test failed, expected {expected}, found {found}")]
+    TestFailed {
+        expected: Box<Value>,
+        found:    Box<Value>,
+    },
+}
+
+/// Why a humanized duration or byte size failed to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum QuantityError {
+    #[error("empty string")]
+    Empty,
+    #[error("expected a number")]
+    ExpectedNumber,
+    #[error("invalid number")]
+    InvalidNumber,
+    #[error("missing unit")]
+    MissingUnit,
+    #[error("unknown unit, expected {0}")]
+    UnknownUnit(&'static str),
+    #[error("overflow")]
+    Overflow,
 }
 
 /// Error type for casting operations within Realme.
 #[derive(Debug, Error)]
 pub struct CastError {
-    origin: String,
-    cause:  String,
+    /// The type of the value that failed to cast, such as `string`. The value
+    /// itself is left out, since it may be sensitive.
+    pub origin: String,
+    /// Why the cast failed.
+    pub cause:  String,
 }
 
 impl CastError {
@@ -83,15 +236,102 @@ impl Display for CastError {
 }
 
 /// Error type for parsing operations within Realme.
+///
+/// The origin names the source that failed to parse, such as a file path or
+/// `inline string #1`, never its content. Where the parser reports a position,
+/// the error also carries a [`Location`] with a snippet of the offending line.
+///
+/// `Display` prints the error on one line. The alternate form (`{:#}`) prints
+/// a multi-line diagnostic that points at the mistake:
+///
+/// ```text
+/// error: extra `=`, expected nothing
+///  --> config.toml:2:8
+///   |
+/// 2 | port = = 8080
+///   |        ^
+/// ```
 #[derive(Debug, Error)]
 pub struct ParseError {
-    origin: String,
-    cause:  String,
+    /// The source that failed to parse.
+    pub origin:   String,
+    /// What the parser reported.
+    pub cause:    String,
+    /// Where the error was found, if the parser reported a position.
+    pub location: Option<Location>,
+}
+
+/// Where in its input a parse error was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The one-based line number.
+    pub line:      usize,
+    /// The one-based column, counted in characters.
+    pub column:    usize,
+    /// The offending line, shortened to about 60 characters around the error.
+    pub snippet:   String,
+    /// The character position of the error in `snippet`, from zero.
+    pub highlight: usize,
+}
+
+/// How many characters of the offending line a snippet keeps.
+const SNIPPET_WIDTH: usize = 60;
+
+impl Location {
+    /// Locates the byte `offset` in `input`, which is clamped to the input.
+    fn new(input: &str, offset: usize) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let text = input[line_start..line_end].trim_end();
+        let column = input[line_start..offset].chars().count() + 1;
+
+        let len = text.chars().count();
+        let start = (column - 1)
+            .saturating_sub(SNIPPET_WIDTH / 2)
+            .min(len.saturating_sub(SNIPPET_WIDTH));
+        let mut snippet: String =
+            text.chars().skip(start).take(SNIPPET_WIDTH).collect();
+        let mut highlight = column - 1 - start;
+        if start > 0 {
+            snippet.insert_str(0, "...");
+            highlight += 3;
+        }
+        if start + SNIPPET_WIDTH < len {
+            snippet.push_str("...");
+        }
+        Self {
+            line: input[..line_start].matches('\n').count() + 1,
+            column,
+            snippet,
+            highlight,
+        }
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Parse {}, error: {}", self.origin, self.cause)
+        if f.alternate() {
+            return self.fmt_diagnostic(f);
+        }
+        write!(f, "Parse {}, error: {}", self.origin, self.cause)?;
+        if let Some(location) = &self.location {
+            write!(
+                f,
+                " at line {}, column {}",
+                location.line, location.column
+            )?;
+            let snippet = location.snippet.trim();
+            if !snippet.is_empty() {
+                write!(f, ": `{snippet}`")?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -100,7 +340,184 @@ impl ParseError {
     pub fn new(origin: String, cause: String) -> Self {
         #[cfg(feature = "tracing")]
         tracing::error!("Parse error: origin:{}, error: {}", origin, cause);
-        Self { origin, cause }
+        Self {
+            origin,
+            cause,
+            location: None,
+        }
+    }
+
+    /// Records that the error was found at byte `offset` of `input`. Only the
+    /// position and a short snippet of its line are kept.
+    #[must_use]
+    pub fn at(mut self, input: &str, offset: usize) -> Self {
+        self.location = Some(Location::new(input, offset));
+        self
+    }
+
+    /// Like [`ParseError::at`], for parsers that report a one-based line and
+    /// column instead of an offset.
+    #[must_use]
+    pub fn at_line(self, input: &str, line: usize, column: usize) -> Self {
+        let line_start: usize = input
+            .split('\n')
+            .take(line.saturating_sub(1))
+            .map(|l| l.len() + 1)
+            .sum();
+        let text = input
+            .get(line_start..)
+            .and_then(|rest| rest.split('\n').next())
+            .unwrap_or_default();
+        let column = text
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(text.len(), |(i, _)| i);
+        self.at(input, line_start + column)
+    }
+
+    /// Replaces the origin, e.g. a format name given by a parser, with the
+    /// name of the source being parsed.
+    #[must_use]
+    pub fn with_origin<O: Into<String>>(mut self, origin: O) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    /// Drops the snippet of the offending line, keeping its position, for
+    /// sources whose content is sensitive.
+    #[must_use]
+    pub fn without_snippet(mut self) -> Self {
+        if let Some(location) = &mut self.location {
+            location.snippet.clear();
+            location.highlight = 0;
+        }
+        self
+    }
+
+    /// Writes the multi-line form shown by `{:#}`.
+    fn fmt_diagnostic(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        writeln!(f, "error: {}", self.cause)?;
+        let Some(location) = &self.location else {
+            return write!(f, " --> {}", self.origin);
+        };
+        let gutter = " ".repeat(location.line.to_string().len());
+        write!(
+            f,
+            "{gutter}--> {}:{}:{}",
+            self.origin, location.line, location.column
+        )?;
+        if location.snippet.is_empty() {
+            return Ok(());
+        }
+        writeln!(f)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", location.line, location.snippet)?;
+        write!(f, "{gutter} | {}^", " ".repeat(location.highlight))
+    }
+}
+
+/// With the `miette` feature, parse errors can be rendered as `miette`
+/// reports, labelled at the position of the error.
+#[cfg(feature = "miette")]
+mod diagnostic {
+    use miette::{
+        Diagnostic,
+        LabeledSpan,
+        MietteError,
+        MietteSpanContents,
+        SourceCode,
+        SourceSpan,
+        SpanContents,
+    };
+
+    use super::{
+        Error,
+        ParseError,
+    };
+
+    impl Diagnostic for ParseError {
+        fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+            Some(Box::new("realme::parse"))
+        }
+
+        fn source_code(&self) -> Option<&dyn SourceCode> {
+            self.location
+                .as_ref()
+                .filter(|location| !location.snippet.is_empty())
+                .map(|_| self as &dyn SourceCode)
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+            let location = self
+                .location
+                .as_ref()
+                .filter(|location| !location.snippet.is_empty())?;
+            let offset = location
+                .snippet
+                .char_indices()
+                .nth(location.highlight)
+                .map_or(location.snippet.len(), |(i, _)| i);
+            let len = usize::from(offset < location.snippet.len());
+            Some(Box::new(std::iter::once(LabeledSpan::new(
+                Some(self.cause.clone()),
+                offset,
+                len,
+            ))))
+        }
+    }
+
+    /// The only code a parse error keeps is the snippet of its line, so that
+    /// is all the report can show.
+    impl SourceCode for ParseError {
+        fn read_span<'a>(
+            &'a self,
+            _span: &SourceSpan,
+            _context_lines_before: usize,
+            _context_lines_after: usize,
+        ) -> Result<Box<dyn SpanContents<'a> + 'a>, MietteError> {
+            let location =
+                self.location.as_ref().ok_or(MietteError::OutOfBounds)?;
+            let snippet = location.snippet.as_bytes();
+            Ok(Box::new(MietteSpanContents::new_named(
+                self.origin.clone(),
+                snippet,
+                SourceSpan::from((0, snippet.len())),
+                location.line - 1,
+                0,
+                1,
+            )))
+        }
+    }
+
+    /// Only parse errors carry a position, including those an adaptor
+    /// failed with; other errors report their message.
+    impl Diagnostic for Error {
+        fn code<'a>(&'a self) -> Option<Box<dyn std::fmt::Display + 'a>> {
+            match self {
+                Self::ParseError(e) => e.code(),
+                Self::AdaptorFailed { source, .. } => source.code(),
+                _ => None,
+            }
+        }
+
+        fn source_code(&self) -> Option<&dyn SourceCode> {
+            match self {
+                Self::ParseError(e) => e.source_code(),
+                Self::AdaptorFailed { source, .. } => source.source_code(),
+                _ => None,
+            }
+        }
+
+        fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+            match self {
+                Self::ParseError(e) => e.labels(),
+                Self::AdaptorFailed { source, .. } => source.labels(),
+                _ => None,
+            }
+        }
     }
 }
 
@@ -123,10 +540,10 @@ impl Display for DeserializeError {
     }
 }
 
+/// Unlike other errors, deserialize errors aren't traced, since their message
+/// may quote a sensitive value.
 impl From<DeserializeError> for Error {
     fn from(value: DeserializeError) -> Self {
-        #[cfg(feature = "tracing")]
-        tracing::error!("Deserialize error: {}", value);
         Self::DeserializeError(value)
     }
 }
@@ -158,11 +575,73 @@ impl From<SerializeError> for Error {
     }
 }
 
-#[derive(Debug, Error)]
-pub struct ExprError(String);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl Display for ExprError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+    #[test]
+    fn test_parse_error_location() {
+        let input = "name = \"realme\"\nport = = 8080\n";
+        let error = ParseError::new("config.toml".to_string(), "bad".into())
+            .at(input, 21);
+        assert_eq!(
+            error.to_string(),
+            "Parse config.toml, error: bad at line 2, column 6: `port = = \
+             8080`"
+        );
+        let error = ParseError::new("config.toml".to_string(), "bad".into())
+            .at_line(input, 2, 6);
+        assert_eq!(error.location, Some(Location::new(input, 21)));
+
+        let long = format!("key = \"{}\" oops", "x".repeat(200));
+        let location = Location::new(&long, long.len() - 2);
+        assert_eq!(location.column, 212);
+        assert!(location.snippet.starts_with("...xxx"));
+        assert!(location.snippet.ends_with("\" oops"));
+        assert!(location.snippet.len() < 70);
+        assert_eq!(location.snippet.chars().nth(location.highlight), Some('p'));
+    }
+
+    #[test]
+    fn test_parse_error_diagnostic() {
+        let input = "name = \"realme\"\nport = = 8080\n";
+        let error =
+            ParseError::new("config.toml".to_string(), "extra `=`".to_string())
+                .at(input, 22);
+        assert_eq!(
+            format!("{error:#}"),
+            "error: extra `=`\n --> config.toml:2:7\n  |\n2 | port = = \
+             8080\n  |       ^"
+        );
+
+        let error =
+            ParseError::new("config.toml".to_string(), "bad".to_string());
+        assert_eq!(format!("{error:#}"), "error: bad\n --> config.toml");
+    }
+
+    #[cfg(feature = "miette")]
+    #[test]
+    fn test_parse_error_miette() {
+        use miette::{
+            NarratableReportHandler,
+            ReportHandler,
+        };
+
+        struct Report<'a>(&'a Error);
+        impl std::fmt::Display for Report<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                NarratableReportHandler::new().debug(self.0, f)
+            }
+        }
+
+        let input = "name = \"realme\"\nport = = 8080\n";
+        let error = Error::ParseError(
+            ParseError::new("config.toml".to_string(), "extra `=`".to_string())
+                .at(input, 22),
+        );
+        let report = Report(&error).to_string();
+        assert!(report.contains("for config.toml starting at line 2"));
+        assert!(report.contains("snippet line 2: port = = 8080"));
+        assert!(report.contains("label at line 2, column 7: extra `=`"));
     }
 }
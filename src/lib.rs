@@ -49,15 +49,33 @@ pub(crate) use errors::Result; /* kept crate-visible for internal
 #[cfg(feature = "watch")]
 pub use realme::SharedRealme;
 pub use realme::{
+    BuildReport,
+    Layer,
+    Override,
     Realme,
     RealmeBuilder,
+    RealmeView,
+    ReloadStats,
+    Warning,
 };
 #[cfg(feature = "macros")]
 pub use realme_macros::*;
-// Keep Map & internal helpers private (least exposure principle)
-pub(crate) use utils::map::Map;
+pub use utils::map::Map;
 // Value related public exports
 pub use value::{
+    ByteSize,
+    Change,
+    Date,
+    Datetime,
+    HumanDuration,
+    MergeConflict,
+    MergeOptions,
+    MergeStrategy,
+    Offset,
+    PatchOperation,
+    Query,
+    Secret,
     Table,
+    Time,
     Value,
 }; // internal re-export for existing uses
@@ -25,8 +25,13 @@ pub use crate::adaptor::source::env::EnvSource;
 #[cfg(feature = "macros")]
 pub use crate::builder;
 pub use crate::{
+    ByteSize,
+    Datetime,
+    HumanDuration,
+    MergeStrategy,
     Realme,
     RealmeBuilder,
+    Secret,
     Table,
     Value,
     adaptor::{
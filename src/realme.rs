@@ -4,55 +4,252 @@ pub mod api;
 pub mod builder;
 #[cfg(feature = "watch")]
 mod shared;
+mod trace;
+mod view;
 #[cfg(feature = "watch")]
 use std::sync::{
     Arc,
     RwLock,
 };
+use std::time::SystemTime;
 
 use serde::{
     Deserialize,
     Serialize,
     de::DeserializeOwned,
 };
+use trace::{
+    Outcome,
+    Trace,
+};
+pub use view::RealmeView;
 
 use crate::{
+    MergeConflict,
+    MergeOptions,
+    MergeStrategy,
     Result,
     prelude::*,
+    value::REDACTED,
 };
 /// Represents a configuration realme with a cache for storing configuration
 /// values.
 #[derive(Deserialize, Clone)]
 pub struct Realme {
     /// The cache storing configuration values.
-    cache:   Value,
+    cache:         Value,
     /// The default configuration values.
     #[serde(skip)]
-    default: Option<Value>,
+    default:       Option<Value>,
+    /// The configuration as loaded from the adaptors, before any value set
+    /// at runtime.
+    #[serde(skip)]
+    loaded:        Value,
+    /// The arrays `set` edited element by element, whose edited copy in
+    /// `default` replaces the loaded array on reload.
+    #[serde(skip)]
+    edited_arrays: Vec<String>,
     /// The builder used to construct this Realme instance.
     #[serde(skip)]
-    builder: RealmeBuilder,
+    builder:       RealmeBuilder,
+    /// What happened while building, besides errors.
+    #[serde(skip)]
+    report:        BuildReport,
+    /// How the reloads went so far.
+    #[serde(skip)]
+    reloads:       ReloadStats,
+    /// The value parsed from each adaptor, in merge order.
+    #[serde(skip)]
+    layers:        Vec<Layer>,
 }
 
 /// Builder for constructing a `Realme` instance.
 #[derive(Default, Clone, Debug)]
 pub struct RealmeBuilder {
     /// List of adaptors used to load configuration.
-    adaptors: Vec<Adaptor>,
+    adaptors:       Vec<Adaptor>,
     /// Optional profile name for configuration.
-    profile:  Option<String>,
+    profile:        Option<String>,
+    /// Array merge strategies per key path.
+    merge_options:  MergeOptions,
+    /// Whether type conflicts between adaptors fail the build.
+    strict:         bool,
+    /// Deprecated keys and the keys that replace them.
+    aliases:        Vec<(String, String)>,
+    /// Queries matching the keys whose values are sensitive.
+    sensitive:      Vec<String>,
+    /// Whether the build goes on after an error to report all of them.
+    collect_errors: bool,
+}
+
+/// The value one adaptor contributed to a `Realme`, as returned by
+/// [`Realme::layers`].
+///
+/// The `Debug` output of a layer hides its sensitive values, or its whole
+/// value if it comes from a sensitive adaptor.
+#[derive(Clone, PartialEq)]
+pub struct Layer {
+    /// A description of the source, e.g. `file config.toml` or `env APP_`.
+    pub source:    String,
+    /// The priority of the adaptor.
+    pub priority:  u8,
+    /// The profile of the adaptor, if any.
+    pub profile:   Option<String>,
+    /// The key path the source was mounted under, if any.
+    pub mount:     Option<String>,
+    /// Whether the adaptor was marked as sensitive.
+    pub sensitive: bool,
+    /// The value parsed from the source, already nested under `mount`.
+    pub value:     Value,
+    /// The paths in `value` matching `RealmeBuilder::sensitive`.
+    secrets:       Vec<String>,
+}
+
+impl std::fmt::Debug for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut layer = f.debug_struct("Layer");
+        layer
+            .field("source", &self.source)
+            .field("priority", &self.priority)
+            .field("profile", &self.profile)
+            .field("mount", &self.mount)
+            .field("sensitive", &self.sensitive);
+        if self.sensitive {
+            layer.field("value", &format_args!("{REDACTED}"));
+        } else {
+            layer.field("value", &redact(&self.value, &self.secrets));
+        }
+        layer.finish()
+    }
+}
+
+/// A problem found while building a `Realme` that did not stop the build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A table or an array was replaced by a value of another type while
+    /// merging the adaptors.
+    TypeConflict(MergeConflict),
+    /// A deprecated key was set and its value moved to the key replacing it.
+    DeprecatedKey { old: String, new: String },
+    /// An optional adaptor was skipped because its file does not exist.
+    SkippedSource { source: String },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TypeConflict(conflict) => {
+                write!(f, "Merge conflict: {conflict}")
+            }
+            Self::DeprecatedKey { old, new } => {
+                write!(
+                    f,
+                    "Config key `{old}` is deprecated, use `{new}` instead"
+                )
+            }
+            Self::SkippedSource { source } => {
+                write!(f, "Skipped optional {source}, it does not exist")
+            }
+        }
+    }
+}
+
+/// A key that a later adaptor set to a different value than an earlier one,
+/// as listed in a [`BuildReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    /// The overridden key, e.g. `server.port`.
+    pub key:      String,
+    /// The source that set the key before, e.g. `file config.toml`.
+    pub previous: String,
+    /// The source that overrode it, e.g. `env APP_`.
+    pub source:   String,
+}
+
+impl std::fmt::Display for Override {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` from {} is overridden by {}",
+            self.key, self.previous, self.source
+        )
+    }
+}
+
+/// What happened while building a `Realme`, besides errors, as returned by
+/// [`Realme::report`].
+///
+/// Printing a report lists one finding per line, which suits configuration
+/// checks in CI.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildReport {
+    /// Problems that did not stop the build: skipped optional sources, type
+    /// conflicts and deprecated keys.
+    pub warnings:   Vec<Warning>,
+    /// Keys set to different values by more than one adaptor.
+    pub overridden: Vec<Override>,
+}
+
+impl BuildReport {
+    /// Returns whether nothing was reported.
+    pub const fn is_empty(&self) -> bool {
+        self.warnings.is_empty() && self.overridden.is_empty()
+    }
+}
+
+impl std::fmt::Display for BuildReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for warning in &self.warnings {
+            writeln!(f, "warning: {warning}")?;
+        }
+        for overridden in &self.overridden {
+            writeln!(f, "note: {overridden}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How the reloads of a `Realme` went, as returned by
+/// [`Realme::reload_stats`].
+///
+/// A `SharedRealme` reloads in the background when a watched file changes;
+/// failures that keep growing, or a last success long ago, mean changes are
+/// not being picked up.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadStats {
+    /// How many reloads succeeded.
+    pub reloads:      u64,
+    /// How many reloads failed, leaving the configuration unchanged.
+    pub failures:     u64,
+    /// When the configuration was last loaded, by the build or a reload.
+    pub last_success: Option<SystemTime>,
+}
+
+impl ReloadStats {
+    /// The stats of a `Realme` that was just built.
+    pub(crate) fn loaded() -> Self {
+        Self {
+            last_success: Some(SystemTime::now()),
+            ..Self::default()
+        }
+    }
 }
 
 #[cfg(feature = "watch")]
 /// A thread-safe shared reference to a `Realme` instance.
+///
+/// Its [`reload_stats`](Realme::reload_stats) tell whether the reloads in
+/// the background succeed.
 pub type SharedRealme = Arc<RwLock<Realme>>;
 
 impl std::fmt::Debug for Realme {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let paths = self.sensitive_paths();
         write!(
             f,
             "Realme {{ cache: {:?}, default: {:?} }}",
-            self.cache, self.default
+            redact(&self.cache, &paths),
+            self.default.as_ref().map(|default| redact(default, &paths))
         )
     }
 }
@@ -88,8 +285,14 @@ impl Realme {
     /// Returns a `Result<T, Error>` which is `Ok` containing the deserialized
     /// type if successful, or an `Err` containing a `Error` if the
     /// operation fails.
+    ///
+    /// An error that would quote a sensitive value is replaced with
+    /// `Error::Redacted`.
     pub fn try_deserialize<T: DeserializeOwned>(&self) -> Result<T> {
-        self.cache.clone().try_deserialize()
+        self.cache
+            .clone()
+            .try_deserialize()
+            .map_err(|e| self.redact_quoted(e))
     }
 
     /// Attempts to serialize a given object into a new `Realme` instance.
@@ -112,12 +315,85 @@ impl Realme {
     pub fn try_serialize<T: Serialize>(from: &T) -> Result<Self> {
         let cache = Value::try_serialize(from)?;
         Ok(Self {
-            cache:   cache.clone(),
-            default: Some(cache),
-            builder: RealmeBuilder::new(),
+            cache:         cache.clone(),
+            default:       Some(cache),
+            loaded:        Value::default(),
+            edited_arrays: Vec::new(),
+            builder:       RealmeBuilder::new(),
+            report:        BuildReport::default(),
+            reloads:       ReloadStats::default(),
+            layers:        Vec::new(),
         })
     }
 
+    /// Returns the warnings collected while building, such as a table in one
+    /// file overridden by a string in another, or a deprecated key in use.
+    ///
+    /// Build with `RealmeBuilder::strict` to turn type conflicts into an
+    /// error instead.
+    pub fn warnings(&self) -> &[Warning] {
+        &self.report.warnings
+    }
+
+    /// Returns the report of the last build: its warnings, and the keys
+    /// that later adaptors overrode.
+    pub const fn report(&self) -> &BuildReport {
+        &self.report
+    }
+
+    /// Returns how many reloads succeeded and failed, and when the
+    /// configuration was last loaded.
+    pub const fn reload_stats(&self) -> &ReloadStats {
+        &self.reloads
+    }
+
+    /// Returns the value parsed from each adaptor, in the order they were
+    /// merged, along with the adaptor's metadata.
+    ///
+    /// Values set at runtime with `set` are not part of any layer.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns a copy of the configuration with every sensitive value
+    /// replaced by `"[REDACTED]"`, safe to log or display.
+    ///
+    /// Values are sensitive if their key matches a query passed to
+    /// `RealmeBuilder::sensitive`, or if they come from an adaptor marked
+    /// with `Adaptor::sensitive`.
+    pub fn redacted(&self) -> Value {
+        redact(&self.cache, &self.sensitive_paths())
+    }
+
+    /// Returns whether the value at `key`, or a value below it, is sensitive.
+    pub fn is_sensitive<K: AsRef<str>>(&self, key: K) -> bool {
+        let key = key.as_ref();
+        let nested = |outer: &str, inner: &str| {
+            inner.strip_prefix(outer).is_some_and(|rest| {
+                outer.is_empty() ||
+                    rest.is_empty() ||
+                    rest.starts_with(['.', '['])
+            })
+        };
+        self.sensitive_paths()
+            .iter()
+            .any(|path| nested(path, key) || nested(key, path))
+    }
+
+    /// The paths of every sensitive value in the cache or the defaults.
+    fn sensitive_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        for value in std::iter::once(&self.cache).chain(&self.default) {
+            paths.extend(self.builder.sensitive_paths(value));
+        }
+        for layer in self.layers.iter().filter(|layer| layer.sensitive) {
+            paths.extend(layer.value.keys());
+        }
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+
     /// Reloads the Realme instance from its builder.
     ///
     /// This method rebuilds the Realme instance using the current builder
@@ -127,13 +403,37 @@ impl Realme {
     ///
     /// Returns a `Result<(), Error>` which is `Ok(())` if the reload was
     /// successful, or an `Err` containing a `Error` if the operation fails.
+    /// A failed reload leaves the configuration unchanged, and is counted in
+    /// [`reload_stats`](Self::reload_stats).
     pub fn reload(&mut self) -> Result<()> {
-        let mut new_realme = self.builder.clone().build()?;
+        let trace =
+            Trace::reload(self.reloads.reloads + self.reloads.failures + 1);
+        let mut new_realme = match self.builder.clone().build() {
+            Ok(realme) => realme,
+            Err(e) => {
+                self.reloads.failures += 1;
+                trace.finish(Outcome::Error, None);
+                return Err(e);
+            }
+        };
         if let Some(default) = self.default.take() {
-            new_realme.cache.merge(&default);
+            let options = self.edited_arrays.iter().fold(
+                self.builder.merge_options.clone(),
+                |options, root| {
+                    options.path(root.clone(), MergeStrategy::Replace)
+                },
+            );
+            new_realme.cache.merge_with(&default, &options);
             new_realme.default = Some(default);
+            new_realme.edited_arrays = std::mem::take(&mut self.edited_arrays);
         }
+        new_realme.reloads = ReloadStats {
+            reloads:      self.reloads.reloads + 1,
+            failures:     self.reloads.failures,
+            last_success: new_realme.reloads.last_success,
+        };
         *self = new_realme;
+        trace.finish(Outcome::Ok, Some(self.cache.keys().len()));
         Ok(())
     }
 
@@ -141,3 +441,17 @@ impl Realme {
     //     self.cache.validate()
     // }
 }
+
+/// Returns a copy of `value` with the values at `paths` redacted.
+fn redact(value: &Value, paths: &[String]) -> Value {
+    let mut redacted = value.clone();
+    for path in paths {
+        // `get_mut` inserts missing keys, so look before writing.
+        if value.get(path.as_str()).is_some_and(|v| *v != Value::Null) &&
+            let Some(slot) = redacted.get_mut(path.as_str())
+        {
+            *slot = Value::String(REDACTED.to_string());
+        }
+    }
+    redacted
+}
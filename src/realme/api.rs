@@ -1,9 +1,12 @@
+use std::time::Duration;
+
 use serde::{
     Serialize,
     de::DeserializeOwned,
 };
 
 use crate::{
+    Error,
     Map,
     Result,
     Value,
@@ -79,7 +82,7 @@ impl Realme {
     ///
     /// An `Option<V>` which is `Some` if the key exists and the value can be
     /// deserialized, or `None` if the key doesn't exist or deserialization
-    /// fails.
+    /// fails. Use `try_get` to tell the two apart.
     ///
     /// # Example
     ///
@@ -120,9 +123,238 @@ impl Realme {
             .and_then(|v| v.clone().try_deserialize().ok())
     }
 
+    /// Retrieves and deserializes the `Value` associated with the given key,
+    /// reporting why it couldn't.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` if the key doesn't exist, or an error naming the key if the
+    /// key is invalid or the value can't be deserialized into `V`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("server.port", "eighty").expect("set config");
+    ///
+    /// assert!(realme.try_get::<u16, _>("server.port").is_err());
+    /// assert_eq!(realme.try_get::<u16, _>("server.workers").unwrap(), None);
+    /// ```
+    pub fn try_get<V, K: AsRef<str>>(&self, key: K) -> Result<Option<V>>
+    where
+        V: DeserializeOwned,
+    {
+        self.cache
+            .try_get(key.as_ref())
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Retrieves and deserializes the `Value` associated with the given key,
+    /// failing with `Error::MissingKey` if it doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("server.port", 8080).expect("set config");
+    ///
+    /// assert_eq!(realme.require::<u16, _>("server.port").unwrap(), 8080);
+    /// assert!(realme.require::<String, _>("server.host").is_err());
+    /// ```
+    pub fn require<V, K: AsRef<str>>(&self, key: K) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        self.cache
+            .require(key.as_ref())
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Retrieves and deserializes the `Value` associated with the given key,
+    /// or returns `default` if it doesn't exist.
+    ///
+    /// A value that exists but can't be deserialized into `V` is an error
+    /// rather than falling back to `default`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let realme = Realme::builder().build().expect("build config");
+    ///
+    /// assert_eq!(realme.get_or("server.workers", 4_u16).unwrap(), 4);
+    /// ```
+    pub fn get_or<V, K: AsRef<str>>(&self, key: K, default: V) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        self.cache
+            .get_or(key.as_ref(), default)
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Hides what an error about the value at `path` says of it, if the
+    /// value is sensitive. The key the error names is kept, since only the
+    /// value may be secret.
+    pub(super) fn redact_error(&self, path: &str, error: Error) -> Error {
+        if !self.is_sensitive(path) {
+            return error;
+        }
+        match error {
+            Error::MissingKey(_) | Error::InvalidKey { .. } => error,
+            Error::InvalidValue { key, .. } => Error::InvalidValue {
+                key,
+                source: Box::new(Error::Redacted),
+            },
+            _ => Error::Redacted,
+        }
+    }
+
+    /// Replaces an error that quotes a sensitive value with
+    /// `Error::Redacted`, for errors that don't name the key they are about,
+    /// such as those of `try_deserialize`.
+    pub(super) fn redact_quoted(&self, error: Error) -> Error {
+        let message = error.to_string();
+        let quoted = self
+            .sensitive_paths()
+            .iter()
+            .filter_map(|path| self.cache.get(path.as_str()))
+            .any(|value| quotes(&message, value));
+        if quoted { Error::Redacted } else { error }
+    }
+
+    /// Returns the path of every setting, sorted, e.g. `server.port` or
+    /// `server.hosts[0]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("server.port", 8080).expect("set config");
+    /// realme.set("server.hosts", ["a", "b"]).expect("set config");
+    ///
+    /// assert_eq!(realme.keys(), vec![
+    ///     "server.hosts[0]",
+    ///     "server.hosts[1]",
+    ///     "server.port"
+    /// ]);
+    /// ```
+    pub fn keys(&self) -> Vec<String> {
+        self.cache.keys()
+    }
+
+    /// Returns whether a value exists at the given key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("server.port", 8080).expect("set config");
+    ///
+    /// assert!(realme.contains("server"));
+    /// assert!(!realme.contains("server.host"));
+    /// ```
+    pub fn contains<K: AsRef<str>>(&self, key: K) -> bool {
+        self.cache.contains(key.as_ref())
+    }
+
+    /// Returns the path and value of every setting matching `query`, which
+    /// may use wildcards, recursive descent and slices. See
+    /// [`Value::query`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("upstreams.api.timeout", 30).expect("set config");
+    /// realme
+    ///     .set("upstreams.auth.host", "auth")
+    ///     .expect("set config");
+    ///
+    /// let missing: Vec<_> = realme
+    ///     .query("upstreams.*")
+    ///     .expect("query config")
+    ///     .into_iter()
+    ///     .filter(|(_, upstream)| upstream.get("timeout").is_none())
+    ///     .map(|(path, _)| path)
+    ///     .collect();
+    /// assert_eq!(missing, vec!["upstreams.auth"]);
+    /// ```
+    pub fn query(&self, query: &str) -> Result<Vec<(String, &Value)>> {
+        self.cache.query(query)
+    }
+
+    /// Iterates over the top-level entries of the configuration, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Value)> {
+        match &self.cache {
+            Value::Table(table) => Some(table.iter()),
+            _ => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    /// Retrieves the value at the given key as a `Duration`.
+    ///
+    /// Humanized strings such as `"30s"`, `"1h30m"` or `"250ms"` are accepted,
+    /// as well as plain numbers, which are read as seconds. See
+    /// [`HumanDuration`](crate::HumanDuration) for the units.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("server.timeout", "1m30s").expect("set config");
+    ///
+    /// assert_eq!(
+    ///     realme.get_duration("server.timeout"),
+    ///     Some(Duration::from_secs(90))
+    /// );
+    /// ```
+    pub fn get_duration<K: AsRef<str>>(&self, key: K) -> Option<Duration> {
+        self.get_as::<HumanDuration, _>(key).map(Duration::from)
+    }
+
+    /// Retrieves the value at the given key as a number of bytes.
+    ///
+    /// Humanized strings such as `"512MiB"` or `"10GB"` are accepted, as well
+    /// as plain integers. See [`ByteSize`](crate::ByteSize) for the units.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("cache.size", "512MiB").expect("set config");
+    ///
+    /// assert_eq!(realme.get_bytes("cache.size"), Some(512 * 1024 * 1024));
+    /// ```
+    pub fn get_bytes<K: AsRef<str>>(&self, key: K) -> Option<u64> {
+        self.get_as::<ByteSize, _>(key).map(u64::from)
+    }
+
     /// Sets a value for the given key in the configuration.
     ///
     /// This method updates both the cache and the default configuration.
+    /// Array elements can be replaced with `key[index]` or appended with
+    /// `key[+]`; see [`Value::set`].
     ///
     /// # Arguments
     ///
@@ -159,18 +391,82 @@ impl Realme {
         key: K,
         value: V,
     ) -> Result<()> {
+        let key = key.as_ref();
         let value = Value::try_serialize(&value)?;
-        self.cache.set(key.as_ref(), value.clone())?;
-        if let Some(default) = &mut self.default {
-            default.set(key.as_ref(), value)?;
+        self.cache.set(key, value.clone())?;
+        let default =
+            self.default.get_or_insert_with(|| Value::Table(Map::new()));
+        // An edit inside an array keeps the whole edited array, so it is
+        // re-applied as is on reload.
+        if let Some(root) = self.cache.array_root(key) &&
+            let Some(array) = self.cache.get(root.as_str())
+        {
+            default.set(root.as_str(), array.clone())?;
+            if !self.edited_arrays.contains(&root) {
+                self.edited_arrays.push(root);
+            }
         } else {
-            let mut tmp = Value::Table(Map::new());
-            tmp.set(key.as_ref(), value)?;
-            self.default = Some(tmp);
+            default.set(key, value)?;
+            self.edited_arrays.retain(|root| root != key);
         }
         Ok(())
     }
 
+    /// Removes the value for the given key from the configuration.
+    ///
+    /// This method deletes the key from the default configuration that `set`
+    /// writes to, so the override is not re-applied on reload, and gives the
+    /// key back the value the loaded sources gave it, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to remove.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` holding the removed `Value` from the cache, or `None` if the
+    /// key didn't exist, or an error if the loaded value could not be put
+    /// back.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme.set("feature.enabled", true).expect("set config");
+    ///
+    /// assert_eq!(
+    ///     realme.unset("feature.enabled").expect("unset config"),
+    ///     Some(Value::Boolean(true))
+    /// );
+    /// assert_eq!(realme.get("feature.enabled"), None);
+    /// ```
+    pub fn unset<K: AsRef<str>>(&mut self, key: K) -> Result<Option<Value>> {
+        let key = key.as_ref();
+        // `set` overrides whole arrays, so that is what falls back to the
+        // loaded value.
+        let path = self
+            .cache
+            .array_root(key)
+            .unwrap_or_else(|| key.to_string());
+        if let Some(default) = &mut self.default {
+            default.remove(key);
+        }
+        let removed = self.cache.remove(key);
+        let overridden = self
+            .default
+            .as_ref()
+            .is_some_and(|default| default.get(path.as_str()).is_some());
+        if !overridden {
+            self.edited_arrays.retain(|root| *root != path);
+        }
+        if !overridden && let Some(loaded) = self.loaded.get(path.as_str()) {
+            self.cache.set(path.as_str(), loaded.clone())?;
+        }
+        Ok(removed)
+    }
+
     /// Merges another `Realme` instance into this one.
     ///
     /// This method combines the cache and default configurations of both
@@ -215,3 +511,14 @@ impl Realme {
         Ok(())
     }
 }
+
+/// Whether `message` contains the text of `value`, or of any value below it.
+fn quotes(message: &str, value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty() && message.contains(s.as_str()),
+        Value::Array(array) => array.iter().any(|v| quotes(message, v)),
+        Value::Table(table) => table.values().any(|v| quotes(message, v)),
+        other => message.contains(&other.to_string()),
+    }
+}
@@ -1,8 +1,24 @@
-use super::Realme;
+use std::cmp::Ordering;
+
+use super::{
+    Realme,
+    ReloadStats,
+    trace::{
+        Outcome,
+        Trace,
+    },
+};
 use crate::{
+    BuildReport,
     Error,
     Map,
+    MergeConflict,
+    MergeOptions,
+    Override,
+    Query,
+    Warning,
     prelude::*,
+    realme::Layer,
 };
 
 /// A builder for creating a `Realme` instance.
@@ -57,6 +73,77 @@ impl RealmeBuilder {
         self
     }
 
+    /// Sets how arrays at the given key path are merged across adaptors.
+    ///
+    /// This takes precedence over a strategy set on the adaptor itself.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new()
+    ///     .load(...)
+    ///     .merge_strategy("allowed_ips", MergeStrategy::Union);
+    /// ```
+    #[must_use]
+    pub fn merge_strategy(
+        mut self,
+        path: impl Into<String>,
+        strategy: MergeStrategy,
+    ) -> Self {
+        self.merge_options = self.merge_options.path(path, strategy);
+        self
+    }
+
+    /// Makes an explicit null in a later adaptor delete the key inherited from
+    /// earlier ones, instead of overwriting it with null.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new().load(...).null_deletes(true);
+    /// ```
+    #[must_use]
+    pub fn null_deletes(mut self, enabled: bool) -> Self {
+        self.merge_options = self.merge_options.null_deletes(enabled);
+        self
+    }
+
+    /// Makes a string equal to `marker` in a later adaptor delete the key
+    /// inherited from earlier ones. Useful for formats without null, such as
+    /// TOML and environment variables.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new().load(...).tombstone("~delete");
+    /// ```
+    #[must_use]
+    pub fn tombstone(mut self, marker: impl Into<String>) -> Self {
+        self.merge_options = self.merge_options.tombstone(marker);
+        self
+    }
+
+    /// Marks the values matching `key` as sensitive, e.g. `database.password`
+    /// or `..token`. Keys use the same syntax as `Value::query`.
+    ///
+    /// Sensitive values are redacted in `Realme`'s `Debug` output and in
+    /// errors about them, and `Realme::redacted` hides them. The build fails
+    /// if `key` is not a valid query.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new()
+    ///     .load(...)
+    ///     .sensitive("database.password")
+    ///     .sensitive("..api_key");
+    /// ```
+    #[must_use]
+    pub fn sensitive(mut self, key: impl Into<String>) -> Self {
+        self.sensitive.push(key.into());
+        self
+    }
+
     /// Constructs a `Realme` instance using the accumulated adaptors and
     /// profile.
     ///
@@ -70,31 +157,291 @@ impl RealmeBuilder {
     /// * `Result<Realme, Error>` - A `Result` containing the constructed
     ///   `Realme` instance or an `Error` if the build process fails.
     pub fn build(mut self) -> Result<Realme, Error> {
-        self.check_profile()?;
-        self.adaptors.sort_by(|a, b| a.priority.cmp(&b.priority));
-        let mut cache = Value::Table(Map::new());
-        self.adaptors.iter().try_for_each(|adaptor| {
-            adaptor.parse().and_then(|value| match value {
-                Value::Table(table) => {
-                    cache.merge(&Value::Table(table));
-                    Ok(())
-                }
-                Value::Null => Ok(()),
-                _ => Err(Error::new_build_error(
-                    "Adaptor parse result is not a table".to_string(),
-                )),
-            })
-        })?;
+        let (cache, layers, report) = self.load_layers(|_| Ok(()))?;
 
         Ok(Realme {
+            loaded: cache.clone(),
             cache,
             default: None,
+            edited_arrays: Vec::new(),
             builder: self,
+            report,
+            reloads: ReloadStats::loaded(),
+            layers,
         })
     }
 
-    pub(crate) fn check_profile(&mut self) -> Result<(), Error> {
+    /// Checks the profile, then parses every adaptor in order of priority
+    /// and merges the results.
+    ///
+    /// `before_parse` runs for each adaptor before it is parsed. Returns the
+    /// merged cache, the parsed layers and the report of what happened on the
+    /// way.
+    pub(crate) fn load_layers<F>(
+        &mut self,
+        before_parse: F,
+    ) -> Result<(Value, Vec<Layer>, BuildReport), Error>
+    where
+        F: FnMut(&Adaptor) -> Result<(), Error>,
+    {
+        let trace = Trace::build(self.adaptors.len());
+        let result = self.merge_layers(before_parse);
+        match &result {
+            Ok((cache, ..)) => {
+                trace.finish(Outcome::Ok, Some(cache.keys().len()));
+            }
+            Err(_) => trace.finish(Outcome::Error, None),
+        }
+        result
+    }
+
+    /// Does the work of `load_layers`, inside its span.
+    fn merge_layers<F>(
+        &mut self,
+        mut before_parse: F,
+    ) -> Result<(Value, Vec<Layer>, BuildReport), Error>
+    where
+        F: FnMut(&Adaptor) -> Result<(), Error>,
+    {
+        let mut failures = Failures::new(self.collect_errors);
+        if let Err(e) = self.check_profile() {
+            failures.add(e)?;
+        }
+        self.adaptors.sort_by_key(|a| a.priority);
+        for key in &self.sensitive {
+            if let Err(e) = key.parse::<Query>() {
+                failures.add(Error::InvalidSensitiveKey {
+                    key:    key.clone(),
+                    source: Box::new(e),
+                })?;
+            }
+        }
+        let mut cache = Value::Table(Map::new());
+        let mut conflicts = Vec::new();
+        let mut warnings = Vec::new();
+        let mut layers = Vec::with_capacity(self.adaptors.len());
+        for adaptor in &self.adaptors {
+            let trace = Trace::parse(&adaptor.describe());
+            let parsed = before_parse(adaptor).and_then(|()| adaptor.parse());
+            let value = match parsed {
+                Ok(value) => {
+                    trace.finish(Outcome::Ok, Some(value.keys().len()));
+                    value
+                }
+                Err(Error::FileNotFound { .. }) if adaptor.optional => {
+                    trace.finish(Outcome::Skipped, None);
+                    warnings.push(Warning::SkippedSource {
+                        source: adaptor.describe(),
+                    });
+                    continue;
+                }
+                Err(e) => {
+                    trace.finish(Outcome::Error, None);
+                    // The offending line of a sensitive source may hold a
+                    // secret.
+                    let e = if adaptor.sensitive {
+                        e.without_snippet()
+                    } else {
+                        e
+                    };
+                    failures.add(Error::AdaptorFailed {
+                        adaptor: adaptor.describe(),
+                        source:  Box::new(e),
+                    })?;
+                    continue;
+                }
+            };
+            match &value {
+                Value::Table(_) => conflicts.extend(
+                    cache.merge_with(&value, &self.merge_options_for(adaptor)),
+                ),
+                Value::Null => {}
+                other => {
+                    failures.add(Error::NotATable {
+                        adaptor: adaptor.describe(),
+                        found:   other.value_type(),
+                    })?;
+                    continue;
+                }
+            }
+            layers.push(Layer {
+                source: adaptor.describe(),
+                priority: adaptor.priority,
+                profile: adaptor.profile.clone(),
+                mount: adaptor.mount.clone(),
+                sensitive: adaptor.sensitive,
+                secrets: self.sensitive_paths(&value),
+                value,
+            });
+        }
+        self.finish_cache(
+            &mut cache,
+            &layers,
+            conflicts,
+            &mut warnings,
+            &mut failures,
+        )?;
+        failures.finish()?;
+        let report = BuildReport {
+            warnings,
+            overridden: overrides(&layers),
+        };
+        Ok((cache, layers, report))
+    }
+
+    /// Makes the build go on after an error, so that it fails with every
+    /// error at once as [`Error::BuildErrors`] instead of just the first.
+    ///
+    /// Every adaptor is parsed, and type conflicts in strict mode and
+    /// conflicting aliases are all reported. Useful to check configuration
+    /// in CI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new().load(...).collect_errors(true);
+    /// ```
+    #[must_use]
+    pub const fn collect_errors(mut self, collect: bool) -> Self {
+        self.collect_errors = collect;
+        self
+    }
+
+    /// Makes type conflicts between adaptors fail the build.
+    ///
+    /// Without it, a table in one adaptor that another adaptor overrides with
+    /// a scalar (or the other way round) is only reported by
+    /// `Realme::warnings`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new().load(...).strict(true);
+    /// ```
+    #[must_use]
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Declares `old` as a deprecated alias of `new`.
+    ///
+    /// After all adaptors are merged, a value found at `old` is moved to `new`
+    /// and a [`Warning::DeprecatedKey`] is reported. If both keys are set to
+    /// different values, the one from the adaptor with the higher priority
+    /// wins. The build fails if a single adaptor sets both to different
+    /// values, or if either key is not a valid key expression.
+    ///
+    /// # Examples
+    ///
+    /// ```rust ignore
+    /// let builder = RealmeBuilder::new()
+    ///     .load(...)
+    ///     .alias("database.uri", "database.url");
+    /// ```
+    #[must_use]
+    pub fn alias(
+        mut self,
+        old: impl Into<String>,
+        new: impl Into<String>,
+    ) -> Self {
+        self.aliases.push((old.into(), new.into()));
+        self
+    }
+
+    /// Applies the aliases to the merged `cache` of `layers` and adds the
+    /// warnings to `warnings`, failing on type conflicts in strict mode.
+    fn finish_cache(
+        &self,
+        cache: &mut Value,
+        layers: &[Layer],
+        conflicts: Vec<MergeConflict>,
+        warnings: &mut Vec<Warning>,
+        failures: &mut Failures,
+    ) -> Result<(), Error> {
+        if self.strict && !conflicts.is_empty() {
+            failures.add(Error::TypeConflicts(conflicts))?;
+        } else {
+            warnings.extend(conflicts.into_iter().map(Warning::TypeConflict));
+        }
+        for (old, new) in &self.aliases {
+            let (value, existing) = match (
+                cache.try_get::<_, Value>(old.as_str()),
+                cache.try_get::<_, Value>(new.as_str()),
+            ) {
+                (Err(e), _) | (_, Err(e)) => {
+                    failures.add(e)?;
+                    continue;
+                }
+                (Ok(None), _) => continue,
+                (Ok(Some(value)), Ok(existing)) => (value, existing),
+            };
+            // The key set by the adaptor with the higher priority wins,
+            // unless a single adaptor sets both.
+            let last_set = |key: &str| {
+                layers
+                    .iter()
+                    .rposition(|layer| layer.value.get(key).is_some())
+            };
+            match existing {
+                None => {
+                    cache.set(new.as_str(), value)?;
+                }
+                Some(existing) if existing == value => {}
+                Some(_) => match last_set(old).cmp(&last_set(new)) {
+                    Ordering::Less => {}
+                    Ordering::Greater => {
+                        cache.set(new.as_str(), value)?;
+                    }
+                    Ordering::Equal => {
+                        failures.add(Error::AliasConflict {
+                            old: old.clone(),
+                            new: new.clone(),
+                        })?;
+                        continue;
+                    }
+                },
+            }
+            cache.remove(old.as_str());
+            remove_empty_parents(cache, old);
+            warnings.push(Warning::DeprecatedKey {
+                old: old.clone(),
+                new: new.clone(),
+            });
+        }
+        #[cfg(feature = "tracing")]
+        for warning in warnings.iter() {
+            tracing::warn!("{}", warning);
+        }
+        Ok(())
+    }
+
+    /// The paths in `value` matching the sensitive keys.
+    pub(crate) fn sensitive_paths(&self, value: &Value) -> Vec<String> {
+        self.sensitive
+            .iter()
+            .filter_map(|key| value.query(key).ok())
+            .flatten()
+            .map(|(path, _)| path)
+            .collect()
+    }
+
+    /// The merge options used when merging `adaptor` into the cache.
+    pub(crate) fn merge_options_for(&self, adaptor: &Adaptor) -> MergeOptions {
+        self.merge_options
+            .clone()
+            .strategy(adaptor.merge_strategy.clone().unwrap_or_default())
+    }
+
+    fn check_profile(&mut self) -> Result<(), Error> {
         let mut profile_not_found = self.profile.is_some();
+        let mut available: Vec<String> = self
+            .adaptors
+            .iter()
+            .filter_map(|adaptor| adaptor.profile.clone())
+            .collect();
+        available.sort();
+        available.dedup();
         self.adaptors.retain(|adaptor| {
             match (&adaptor.profile, &self.profile) {
                 (None, _) => true,
@@ -108,13 +455,85 @@ impl RealmeBuilder {
             }
         });
         if profile_not_found {
-            return Err(Error::new_build_error(format!(
-                "Can not find profile {}",
-                self.profile.as_ref().expect("Profile is not set")
-            )));
+            return Err(Error::ProfileNotFound {
+                name: self.profile.clone().expect("Profile is not set"),
+                available,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The errors found while building. Unless they are collected, the first one
+/// stops the build.
+struct Failures {
+    collect: bool,
+    errors:  Vec<Error>,
+}
+
+impl Failures {
+    const fn new(collect: bool) -> Self {
+        Self {
+            collect,
+            errors: Vec::new(),
         }
+    }
+
+    /// Records `error`, or returns it if errors aren't collected.
+    fn add(&mut self, error: Error) -> Result<(), Error> {
+        if !self.collect {
+            return Err(error);
+        }
+        self.errors.push(error);
         Ok(())
     }
+
+    /// Fails with every recorded error, if there are any.
+    fn finish(self) -> Result<(), Error> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::BuildErrors(self.errors))
+        }
+    }
+}
+
+/// Lists the leaves of each layer that an earlier layer set to a different
+/// value, along with the last layer that set them.
+fn overrides(layers: &[Layer]) -> Vec<Override> {
+    let mut overridden = Vec::new();
+    for (i, layer) in layers.iter().enumerate() {
+        for (key, value) in layer.value.flatten() {
+            let previous = layers[..i].iter().rev().find_map(|earlier| {
+                earlier.value.get(key.as_str()).map(|v| (earlier, v))
+            });
+            if let Some((earlier, previous)) = previous &&
+                previous != value
+            {
+                overridden.push(Override {
+                    key,
+                    previous: earlier.source.clone(),
+                    source: layer.source.clone(),
+                });
+            }
+        }
+    }
+    overridden
+}
+
+/// Removes the tables along `path` that became empty once its value was
+/// moved away.
+fn remove_empty_parents(cache: &mut Value, path: &str) {
+    let mut path = path;
+    while let Some((parent, _)) = path.rsplit_once('.') {
+        match cache.get(parent) {
+            Some(Value::Table(table)) if table.is_empty() => {
+                cache.remove(parent);
+            }
+            _ => break,
+        }
+        path = parent;
+    }
 }
 
 #[cfg(feature = "macros")]
@@ -140,7 +559,11 @@ mod tests {
     use tempfile::NamedTempFile;
     use toml::toml;
 
-    use crate::prelude::*;
+    use crate::{
+        Override,
+        Warning,
+        prelude::*,
+    };
 
     fn create_temp_toml(content: &str) -> NamedTempFile {
         let mut file =
@@ -262,8 +685,17 @@ mod tests {
                 Adaptor::new(FileSource::<TomlParser>::new(config.path()))
                     .priority(1),
             )
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(config.path()))
+                    .profile("dev"),
+            )
             .profile("non_existent");
-        assert!(builder.build().is_err());
+        let err = builder.build().expect_err("missing profile");
+        assert!(matches!(
+            &err,
+            crate::Error::ProfileNotFound { name, available }
+                if name == "non_existent" && available == &["dev"]
+        ));
     }
 
     #[test]
@@ -311,7 +743,16 @@ mod tests {
             Adaptor::new(FileSource::<TomlParser>::new("non_existent.toml"))
                 .priority(1),
         );
-        assert!(builder.build().is_err());
+        let err = builder.build().expect_err("missing file");
+        let crate::Error::AdaptorFailed { adaptor, source } = &err else {
+            panic!("expected an adaptor failure, got {err:?}");
+        };
+        assert_eq!(adaptor, "file non_existent.toml");
+        assert!(matches!(
+            source.as_ref(),
+            crate::Error::FileNotFound { path } if path.ends_with("non_existent.toml")
+        ));
+        assert!(std::error::Error::source(&err).is_some());
     }
 
     #[test]
@@ -485,4 +926,782 @@ mod tests {
         assert_eq!(realme.get("server.debug"), Some(&Value::Boolean(true)));
         Ok(())
     }
+
+    #[test]
+    fn test_unset_reverts_override_on_reload() -> Result<(), Error> {
+        let config = create_temp_toml(
+            "
+            [server]
+            port = 8080
+        ",
+        );
+        let mut realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()?;
+
+        realme.set("server.port", 9000)?;
+        realme.set("server.debug", true)?;
+        realme.reload()?;
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(9000)));
+
+        assert_eq!(realme.unset("server.port")?, Some(Value::Integer(9000)));
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(8080)));
+        realme.reload()?;
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(8080)));
+        assert_eq!(realme.get("server.debug"), Some(&Value::Boolean(true)));
+        assert_eq!(realme.unset("server.missing")?, None);
+        assert_eq!(realme.unset("server.debug")?, Some(Value::Boolean(true)));
+        assert_eq!(realme.get("server.debug"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unset_array_element_reverts_to_loaded_array() -> Result<(), Error> {
+        let config = create_temp_toml(
+            r#"
+            hosts = ["a", "b", "c"]
+        "#,
+        );
+        let mut realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()?;
+
+        assert_eq!(
+            realme.unset("hosts[1]")?,
+            Some(Value::String("b".to_string()))
+        );
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("hosts"),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+
+        realme.set("hosts[+]", "d")?;
+        realme.unset("hosts[0]")?;
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("hosts"),
+            Some(vec!["b".to_string(), "c".to_string(), "d".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_reapplies_overrides_with_merge_options() -> Result<(), Error>
+    {
+        let config = create_temp_toml(
+            r#"
+            plugins = ["auth"]
+            hosts = ["a", "b"]
+            [database]
+            host = "localhost"
+            pool = 4
+        "#,
+        );
+        let mut realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .merge_strategy("plugins", MergeStrategy::Union)
+            .merge_strategy("hosts", MergeStrategy::Append)
+            .null_deletes(true)
+            .tombstone("~delete")
+            .build()?;
+
+        realme.set("database.pool", Value::Null)?;
+        realme.set("database.host", "~delete")?;
+        realme.set("plugins", vec!["metrics"])?;
+        realme.set("hosts[+]", "c")?;
+        realme.reload()?;
+
+        assert_eq!(realme.get("database.pool"), None);
+        assert_eq!(realme.get("database.host"), None);
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("plugins"),
+            Some(vec!["auth".to_string(), "metrics".to_string()])
+        );
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("hosts"),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_set_array_elements_survives_reload() -> Result<(), Error> {
+        let config = create_temp_toml(
+            r#"
+            hosts = ["a", "b"]
+        "#,
+        );
+        let mut realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()?;
+
+        realme.set("hosts[+]", "c")?;
+        realme.set("hosts[0]", "z")?;
+        assert!(realme.set("hosts[5]", "x").is_err());
+        realme.reload()?;
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("hosts"),
+            Some(vec!["z".to_string(), "b".to_string(), "c".to_string()])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_merge_strategies() -> Result<(), Error> {
+        let base = create_temp_toml(
+            r#"
+            allowed_ips = ["10.0.0.1"]
+            plugins = ["auth"]
+
+            [[servers]]
+            name = "a"
+            port = 1
+        "#,
+        );
+        let overlay = create_temp_toml(
+            r#"
+            allowed_ips = ["10.0.0.2"]
+            plugins = ["auth", "metrics"]
+
+            [[servers]]
+            name = "a"
+            port = 10
+
+            [[servers]]
+            name = "b"
+            port = 2
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(base.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(overlay.path()))
+                    .priority(1)
+                    .merge_strategy(MergeStrategy::Append),
+            )
+            .merge_strategy("plugins", MergeStrategy::Union)
+            .merge_strategy("servers", MergeStrategy::ByKey("name".into()))
+            .build()?;
+
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("allowed_ips"),
+            Some(vec!["10.0.0.1".to_string(), "10.0.0.2".to_string()])
+        );
+        assert_eq!(
+            realme.get_as::<Vec<String>, _>("plugins"),
+            Some(vec!["auth".to_string(), "metrics".to_string()])
+        );
+        assert_eq!(realme.get("servers[0].port"), Some(&Value::Integer(10)));
+        assert_eq!(
+            realme.get("servers[1].name"),
+            Some(&Value::String("b".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_tombstone() -> Result<(), Error> {
+        let base = create_temp_toml(
+            r#"
+            [database]
+            host = "localhost"
+            pool = 4
+        "#,
+        );
+        let overlay = create_temp_toml(
+            r#"
+            [database]
+            pool = "~delete"
+            timeout = "~delete"
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(base.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(overlay.path()))
+                    .priority(1),
+            )
+            .tombstone("~delete")
+            .build()?;
+
+        assert_eq!(
+            realme.get("database.host"),
+            Some(&Value::String("localhost".to_string()))
+        );
+        assert_eq!(realme.get("database.pool"), None);
+        assert_eq!(realme.get("database.timeout"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_type_conflicts() -> Result<(), Error> {
+        let base = create_temp_toml(
+            r#"
+            [database]
+            host = "localhost"
+        "#,
+        );
+        let overlay = create_temp_toml(
+            r#"
+            database = "postgres://localhost/mydb"
+        "#,
+        );
+        let builder = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(base.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(overlay.path()))
+                    .priority(1),
+            );
+
+        let realme = builder.clone().build()?;
+        assert!(matches!(
+            realme.warnings(),
+            [Warning::TypeConflict(conflict)] if conflict.path == "database"
+        ));
+        assert_eq!(
+            realme.get("database"),
+            Some(&Value::String("postgres://localhost/mydb".to_string()))
+        );
+
+        let err = builder.strict(true).build().expect_err("strict build");
+        assert!(err.to_string().contains("table at `database`"));
+        assert!(matches!(
+            &err,
+            crate::Error::TypeConflicts(conflicts) if conflicts.len() == 1
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_mount() -> Result<(), Error> {
+        let app = create_temp_toml(
+            "
+            [services.payments]
+            enabled = true
+        ",
+        );
+        let db = create_temp_toml(
+            r#"
+            url = "postgres://localhost/payments"
+            pool = 4
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(app.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(db.path()))
+                    .mount("services.payments.database"),
+            )
+            .build()?;
+
+        assert_eq!(
+            realme.get("services.payments.enabled"),
+            Some(&Value::Boolean(true))
+        );
+        assert_eq!(
+            realme.get("services.payments.database.pool"),
+            Some(&Value::Integer(4))
+        );
+        assert_eq!(realme.get("url"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_aliases() -> Result<(), Error> {
+        let config = create_temp_toml(
+            r#"
+            [database]
+            uri = "postgres://localhost/mydb"
+
+            [legacy.server]
+            listen = 8080
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .alias("database.uri", "database.url")
+            .alias("legacy.server.listen", "server.port")
+            .alias("unused.key", "other.key")
+            .build()?;
+
+        assert_eq!(
+            realme.get("database.url"),
+            Some(&Value::String("postgres://localhost/mydb".to_string()))
+        );
+        assert_eq!(realme.get("database.uri"), None);
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(8080)));
+        assert_eq!(realme.get("legacy"), None);
+        assert_eq!(realme.warnings(), &[
+            Warning::DeprecatedKey {
+                old: "database.uri".to_string(),
+                new: "database.url".to_string(),
+            },
+            Warning::DeprecatedKey {
+                old: "legacy.server.listen".to_string(),
+                new: "server.port".to_string(),
+            },
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_conflicting_alias() {
+        let config = create_temp_toml(
+            r#"
+            [database]
+            uri = "postgres://old"
+            url = "postgres://new"
+        "#,
+        );
+        let result = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .alias("database.uri", "database.url")
+            .build();
+        assert!(matches!(
+            result,
+            Err(crate::Error::AliasConflict { old, new })
+                if old == "database.uri" && new == "database.url"
+        ));
+    }
+
+    #[test]
+    fn test_build_with_alias_in_higher_priority() -> Result<(), Error> {
+        let base = toml! {
+            [database]
+            url = "postgres://base"
+        };
+        let local = toml! {
+            [database]
+            uri = "postgres://local"
+        };
+        let build = |old_priority, new_priority| {
+            RealmeBuilder::new()
+                .load(
+                    Adaptor::new(SerSource::<SerParser, _>::new(local.clone()))
+                        .priority(old_priority),
+                )
+                .load(
+                    Adaptor::new(SerSource::<SerParser, _>::new(base.clone()))
+                        .priority(new_priority),
+                )
+                .alias("database.uri", "database.url")
+                .build()
+        };
+
+        let realme = build(2, 1)?;
+        assert_eq!(
+            realme.get("database.url"),
+            Some(&Value::String("postgres://local".to_string()))
+        );
+        assert_eq!(realme.get("database.uri"), None);
+        let realme = build(1, 2)?;
+        assert_eq!(
+            realme.get("database.url"),
+            Some(&Value::String("postgres://base".to_string()))
+        );
+        assert_eq!(realme.get("database.uri"), None);
+        assert_eq!(realme.warnings().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_invalid_alias() {
+        let result = RealmeBuilder::new()
+            .load(Adaptor::new(SerSource::<SerParser, _>::new(toml! {
+                a = 1
+            })))
+            .alias("a", "b]")
+            .build();
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidKey { key, .. }) if key == "b]"
+        ));
+    }
+
+    #[test]
+    fn test_build_keeps_layers() -> Result<(), Error> {
+        let base = create_temp_toml(
+            "
+            [server]
+            port = 8080
+        ",
+        );
+        let overlay = create_temp_toml(
+            "
+            [server]
+            port = 9000
+        ",
+        );
+        let realme = RealmeBuilder::new()
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(overlay.path()))
+                    .priority(2)
+                    .profile("prod"),
+            )
+            .load(Adaptor::new(FileSource::<TomlParser>::new(base.path())))
+            .load(
+                Adaptor::new(SerSource::<SerParser, _>::new(toml! {
+                    debug = true
+                }))
+                .priority(1)
+                .mount("app"),
+            )
+            .profile("prod")
+            .build()?;
+
+        let layers = realme.layers();
+        assert_eq!(layers.len(), 3);
+        assert_eq!(layers[0].source, format!("file {}", base.path().display()));
+        assert_eq!(
+            layers[0].value.get("server.port"),
+            Some(&Value::Integer(8080))
+        );
+        assert_eq!(layers[1].mount.as_deref(), Some("app"));
+        assert_eq!(
+            layers[1].value.get("app.debug"),
+            Some(&Value::Boolean(true))
+        );
+        assert_eq!(layers[2].priority, 2);
+        assert_eq!(layers[2].profile.as_deref(), Some("prod"));
+        assert_eq!(
+            layers[2].value.get("server.port"),
+            Some(&Value::Integer(9000))
+        );
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(9000)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_with_sensitive_values() -> Result<(), Error> {
+        let config = create_temp_toml(
+            r#"
+            [database]
+            user = "admin"
+            password = "hunter2"
+            port = "5432x"
+
+            [[upstreams]]
+            token = "tok-1"
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .load(
+                Adaptor::new(SerSource::<SerParser, _>::new(toml! {
+                    api_key = "key-1"
+                }))
+                .sensitive(),
+            )
+            .sensitive("database.password")
+            .sensitive("database.port")
+            .sensitive("..token")
+            .build()?;
+
+        let debug = format!("{realme:?} {:?}", realme.layers());
+        for secret in ["hunter2", "5432x", "tok-1", "key-1"] {
+            assert!(!debug.contains(secret), "{secret} leaked: {debug}");
+        }
+        assert!(debug.contains("admin"));
+
+        let redacted = realme.redacted();
+        assert_eq!(
+            redacted.get("upstreams[0].token"),
+            Some(&Value::String("[REDACTED]".to_string()))
+        );
+        assert_eq!(
+            realme.get_as::<String, _>("api_key").as_deref(),
+            Some("key-1")
+        );
+        assert_eq!(
+            realme
+                .get_as::<Secret<String>, _>("database.password")
+                .map(Secret::into_inner)
+                .as_deref(),
+            Some("hunter2")
+        );
+
+        assert!(realme.is_sensitive("database"));
+        assert!(realme.is_sensitive("upstreams[0].token"));
+        assert!(!realme.is_sensitive("database.user"));
+        let err = realme
+            .try_get::<u16, _>("database.port")
+            .expect_err("invalid port");
+        assert!(!err.to_string().contains("5432x"), "{err}");
+
+        let err = RealmeBuilder::new()
+            .sensitive("a[")
+            .build()
+            .expect_err("invalid query");
+        assert!(err.to_string().contains("a["));
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_hides_snippet_of_sensitive_source() {
+        let config = create_temp_toml("password = \"hunter2");
+        let err = RealmeBuilder::new()
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(config.path()))
+                    .sensitive(),
+            )
+            .build()
+            .expect_err("unterminated string");
+        let crate::Error::AdaptorFailed { source, .. } = &err else {
+            panic!("expected an adaptor failure, got {err:?}");
+        };
+        let crate::Error::ParseError(parse) = &**source else {
+            panic!("expected a parse error, got {source:?}");
+        };
+        assert_eq!(parse.location.as_ref().map(|l| l.line), Some(1));
+        for message in [err.to_string(), format!("{parse:#}")] {
+            assert!(!message.contains("hunter2"), "{message}");
+        }
+
+        let err = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()
+            .expect_err("unterminated string");
+        assert!(err.to_string().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_build_with_custom_parser_error() {
+        struct Upper;
+
+        impl Parser<&str> for Upper {
+            type Error = String;
+            type Item = Value;
+
+            fn parse(args: &str) -> Result<Value, String> {
+                Err(format!("`{args}` is not upper case"))
+            }
+        }
+
+        let config = create_temp_toml("port = 8080");
+        let err = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<Upper>::new(config.path())))
+            .build()
+            .expect_err("custom parser fails");
+        let crate::Error::AdaptorFailed { source, .. } = &err else {
+            panic!("expected an adaptor failure, got {err:?}");
+        };
+        let crate::Error::ParseError(parse) = &**source else {
+            panic!("expected a parse error, got {source:?}");
+        };
+        assert_eq!(parse.origin, format!("file {}", config.path().display()));
+        assert_eq!(parse.cause, "`port = 8080` is not upper case");
+    }
+
+    #[test]
+    fn test_build_with_optional_source() -> Result<(), Error> {
+        let config = create_temp_toml(
+            "
+            [server]
+            port = 8080
+        ",
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new("local.toml"))
+                    .optional(),
+            )
+            .build()?;
+        assert_eq!(realme.get("server.port"), Some(&Value::Integer(8080)));
+        assert_eq!(realme.warnings(), &[Warning::SkippedSource {
+            source: "file local.toml".to_string(),
+        }]);
+        assert_eq!(realme.layers().len(), 1);
+
+        let invalid = create_temp_toml("port = = 1");
+        let result = RealmeBuilder::new()
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(invalid.path()))
+                    .optional(),
+            )
+            .build();
+        assert!(matches!(result, Err(crate::Error::AdaptorFailed { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_collects_errors() {
+        let invalid = create_temp_toml("port = = 1");
+        let config = create_temp_toml(
+            r#"
+            [database]
+            uri = "postgres://old"
+            url = "postgres://new"
+        "#,
+        );
+        let builder = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new("missing.toml")))
+            .load(Adaptor::new(FileSource::<TomlParser>::new(invalid.path())))
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .alias("database.uri", "database.url")
+            .sensitive("a[");
+
+        let err = builder.clone().build().expect_err("first error");
+        assert!(matches!(err, crate::Error::InvalidSensitiveKey { .. }));
+
+        let err = builder
+            .collect_errors(true)
+            .build()
+            .expect_err("all errors");
+        let crate::Error::BuildErrors(errors) = &err else {
+            panic!("expected every error, got {err:?}");
+        };
+        assert_eq!(errors.len(), 4, "{err}");
+        assert!(matches!(
+            errors[0],
+            crate::Error::InvalidSensitiveKey { .. }
+        ));
+        assert!(matches!(
+            &errors[1],
+            crate::Error::AdaptorFailed { source, .. }
+                if matches!(**source, crate::Error::FileNotFound { .. })
+        ));
+        assert!(matches!(
+            &errors[2],
+            crate::Error::AdaptorFailed { source, .. }
+                if matches!(**source, crate::Error::ParseError(_))
+        ));
+        assert!(matches!(errors[3], crate::Error::AliasConflict { .. }));
+        assert!(err.to_string().starts_with("4 errors while building: "));
+    }
+
+    #[cfg(feature = "placeholder")]
+    #[test]
+    fn test_build_with_invalid_template() {
+        let config = create_temp_toml("name = \"{{ missing( }}\"");
+        let err = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()
+            .expect_err("invalid template");
+        let crate::Error::AdaptorFailed { source, .. } = &err else {
+            panic!("expected an adaptor failure, got {err:?}");
+        };
+        let crate::Error::TemplateError { path, source } = &**source else {
+            panic!("expected a template error, got {source:?}");
+        };
+        assert_eq!(path, config.path());
+        assert_eq!(source.kind(), minijinja::ErrorKind::SyntaxError);
+    }
+
+    #[test]
+    fn test_build_collects_profile_error() {
+        let invalid = create_temp_toml("port = = 1");
+        let builder = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(invalid.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new("dev.toml"))
+                    .profile("dev"),
+            )
+            .profile("prod");
+
+        let err = builder.clone().build().expect_err("unknown profile");
+        assert!(matches!(err, crate::Error::ProfileNotFound { .. }));
+
+        let err = builder
+            .collect_errors(true)
+            .build()
+            .expect_err("all errors");
+        let crate::Error::BuildErrors(errors) = &err else {
+            panic!("expected every error, got {err:?}");
+        };
+        assert_eq!(errors.len(), 2, "{err}");
+        assert!(matches!(
+            &errors[0],
+            crate::Error::ProfileNotFound { name, available }
+                if name == "prod" && available == &["dev"]
+        ));
+        assert!(matches!(errors[1], crate::Error::AdaptorFailed { .. }));
+    }
+
+    #[test]
+    fn test_build_report() -> Result<(), Error> {
+        let base = create_temp_toml(
+            r#"
+            [server]
+            host = "localhost"
+            port = 8080
+            [database]
+            uri = "postgres://localhost/mydb"
+        "#,
+        );
+        let overlay = create_temp_toml(
+            r#"
+            [server]
+            host = "localhost"
+            port = 9090
+        "#,
+        );
+        let realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(base.path())))
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new(overlay.path()))
+                    .priority(1),
+            )
+            .load(
+                Adaptor::new(FileSource::<TomlParser>::new("local.toml"))
+                    .priority(2)
+                    .optional(),
+            )
+            .alias("database.uri", "database.url")
+            .build()?;
+
+        let report = realme.report();
+        assert_eq!(report.overridden, vec![Override {
+            key:      "server.port".to_string(),
+            previous: format!("file {}", base.path().display()),
+            source:   format!("file {}", overlay.path().display()),
+        }]);
+        assert_eq!(report.warnings, vec![
+            Warning::SkippedSource {
+                source: "file local.toml".to_string(),
+            },
+            Warning::DeprecatedKey {
+                old: "database.uri".to_string(),
+                new: "database.url".to_string(),
+            },
+        ]);
+        let printed = report.to_string();
+        assert_eq!(printed.lines().count(), 3, "{printed}");
+        assert!(printed.contains("note: `server.port` from file"));
+        assert!(!realme.report().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_stats() -> Result<(), Error> {
+        let config = create_temp_toml("port = 8080");
+        let mut realme = RealmeBuilder::new()
+            .load(Adaptor::new(FileSource::<TomlParser>::new(config.path())))
+            .build()?;
+        let built = realme.reload_stats().clone();
+        assert_eq!((built.reloads, built.failures), (0, 0));
+        assert!(built.last_success.is_some());
+
+        std::fs::write(config.path(), "port = 9090")?;
+        realme.reload()?;
+        assert_eq!(realme.get("port"), Some(&Value::Integer(9090)));
+        assert_eq!(realme.reload_stats().reloads, 1);
+        assert!(realme.reload_stats().last_success >= built.last_success);
+
+        std::fs::write(config.path(), "port = = 1")?;
+        assert!(realme.reload().is_err());
+        assert_eq!(realme.get("port"), Some(&Value::Integer(9090)));
+        let stats = realme.reload_stats();
+        assert_eq!((stats.reloads, stats.failures), (1, 1));
+
+        std::fs::write(config.path(), "port = 7070")?;
+        realme.reload()?;
+        assert_eq!(
+            (
+                realme.reload_stats().reloads,
+                realme.reload_stats().failures
+            ),
+            (2, 1)
+        );
+        Ok(())
+    }
 }
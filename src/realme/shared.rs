@@ -9,10 +9,11 @@ use std::{
     },
 };
 
-use super::SharedRealme;
+use super::{
+    ReloadStats,
+    SharedRealme,
+};
 use crate::{
-    Error,
-    Map,
     Result,
     prelude::*,
 };
@@ -28,21 +29,25 @@ impl RealmeBuilder {
     /// * `Result<SharedRealme, Error>` - A `Result` containing the built
     ///   `SharedRealme` instance or an `Error` if the build process fails.
     pub fn shared_build(mut self) -> Result<SharedRealme> {
-        self.check_profile()?;
-        self.adaptors.sort_by(|a, b| a.priority.cmp(&b.priority));
         let (sender, receiver) = crossbeam::channel::unbounded::<()>();
-        let cache = update_cache(&self.adaptors, &sender)?;
+        let (cache, layers, report) =
+            self.load_layers(|adaptor| adaptor.watcher(sender.clone()))?;
         let shared_realme = Arc::new(RwLock::new(Realme {
+            loaded: cache.clone(),
             cache,
             default: None,
+            edited_arrays: Vec::new(),
             builder: self.clone(),
+            report,
+            reloads: ReloadStats::loaded(),
+            layers,
         }));
 
         let shared_realme_clone = shared_realme.clone();
 
-        std::thread::spawn(move || -> Result<()> {
+        std::thread::spawn(move || {
             // To avoid too many updates, set a debounce time
-            let debounce_duration = Duration::from_millis(1000);
+            let debounce_duration = Duration::from_secs(1);
             // Timeout time
             let timeout_duration = Duration::from_millis(500);
             let mut last_update = Instant::now();
@@ -65,44 +70,28 @@ impl RealmeBuilder {
                 if should_update &&
                     now.duration_since(last_update) >= debounce_duration
                 {
-                    // Update shared_realme
+                    // Update shared_realme. A failed reload keeps the previous
+                    // configuration and is counted in its reload stats.
                     if let Ok(mut realme) = shared_realme_clone.write() {
-                        realme.reload()?;
+                        #[cfg_attr(
+                            not(feature = "tracing"),
+                            allow(unused_variables)
+                        )]
+                        if let Err(e) = realme.reload() {
+                            #[cfg(feature = "tracing")]
+                            tracing::error!("Reload error: {}", e);
+                        }
                     }
                     last_update = now;
                     should_update = false;
                 }
             }
-
-            Ok(())
         });
 
         Ok(shared_realme)
     }
 }
 
-fn update_cache(
-    adaptor: &[Adaptor],
-    sender: &crossbeam::channel::Sender<()>,
-) -> Result<Value> {
-    let mut cache = Value::Table(Map::new());
-    adaptor.iter().try_for_each(|adaptor| {
-        adaptor.watcher(sender.clone()).and_then(|()| {
-            adaptor.parse().and_then(|value| match value {
-                Value::Table(table) => {
-                    cache.merge(&Value::Table(table));
-                    Ok(())
-                }
-                Value::Null => Ok(()),
-                _ => Err(Error::new_build_error(
-                    "Adaptor parse result is not a table".to_string(),
-                )),
-            })
-        })
-    })?;
-    Ok(cache)
-}
-
 // impl SharedRealme {
 //     pub fn get_realme(&self) -> Result<&Realme> {
 //         let Ok(realme) = self.0.read() else {
@@ -0,0 +1,105 @@
+//! Spans around building, parsing and reloading, recorded with the
+//! `tracing` feature and compiled away without it.
+
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// A span opened when an operation starts, which records how long the
+/// operation took and how it ended once it is finished.
+///
+/// Each span has the fields `keys`, `duration` and `outcome`, plus the ones
+/// given by its constructor.
+pub(crate) struct Trace {
+    #[cfg(feature = "tracing")]
+    span:    tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    started: Instant,
+}
+
+/// How an operation ended.
+#[derive(Clone, Copy)]
+pub(crate) enum Outcome {
+    Ok,
+    Skipped,
+    Error,
+}
+
+#[cfg(feature = "tracing")]
+impl Outcome {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Skipped => "skipped",
+            Self::Error => "error",
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $field:ident = $value:expr)*) => {
+        Trace::enter(tracing::info_span!(
+            $name,
+            $($field = $value,)*
+            keys = tracing::field::Empty,
+            duration = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        ))
+    };
+}
+
+#[cfg(feature = "tracing")]
+impl Trace {
+    /// Opens the `realme.build` span around merging `adaptors` adaptors.
+    pub(crate) fn build(adaptors: usize) -> Self {
+        span!("realme.build", adaptors = adaptors)
+    }
+
+    /// Opens the `realme.parse` span around parsing the adaptor described
+    /// by `source`.
+    pub(crate) fn parse(source: &str) -> Self {
+        span!("realme.parse", source = source)
+    }
+
+    /// Opens the `realme.reload` span around the `reload`th reload.
+    pub(crate) fn reload(reload: u64) -> Self {
+        span!("realme.reload", reload = reload)
+    }
+
+    fn enter(span: tracing::Span) -> Self {
+        Self {
+            span:    span.entered(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Records the outcome and duration of the operation, and the number of
+    /// keys it produced if it produced any, then closes the span.
+    pub(crate) fn finish(self, outcome: Outcome, keys: Option<usize>) {
+        if let Some(keys) = keys {
+            self.span.record("keys", keys);
+        }
+        self.span
+            .record("duration", tracing::field::debug(self.started.elapsed()));
+        self.span.record("outcome", outcome.as_str());
+        tracing::debug!("finished");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[allow(clippy::unused_self)]
+impl Trace {
+    pub(crate) const fn build(_: usize) -> Self {
+        Self {}
+    }
+
+    pub(crate) const fn parse(_: &str) -> Self {
+        Self {}
+    }
+
+    pub(crate) const fn reload(_: u64) -> Self {
+        Self {}
+    }
+
+    pub(crate) const fn finish(self, _: Outcome, _: Option<usize>) {}
+}
@@ -0,0 +1,290 @@
+use serde::de::DeserializeOwned;
+
+use crate::{
+    Error,
+    Result,
+    prelude::*,
+};
+
+/// The value a view of a missing key is rooted at.
+static NULL: Value = Value::Null;
+
+/// A read-only view of a `Realme` rooted at a key path, as returned by
+/// [`Realme::sub`].
+///
+/// Keys passed to a view are relative to its root, so a component can take
+/// its slice of the configuration without knowing where it sits in the
+/// application's tree.
+///
+/// # Example
+///
+/// ```rust
+/// use realme::prelude::*;
+///
+/// let mut realme = Realme::builder().build().expect("build config");
+/// realme.set("database.pool.size", 8).expect("set config");
+///
+/// let database = realme.sub("database");
+/// assert_eq!(database.get_as::<u32, _>("pool.size"), Some(8));
+/// assert_eq!(database.sub("pool").get_as::<u32, _>("size"), Some(8));
+/// ```
+#[derive(Clone)]
+pub struct RealmeView<'a> {
+    realme: &'a Realme,
+    /// The key path of the root in `realme`, to tell sensitive keys apart.
+    path:   String,
+    root:   &'a Value,
+}
+
+/// The `Debug` output of a view names its path and leaves the values out,
+/// since they may be sensitive.
+impl std::fmt::Debug for RealmeView<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RealmeView")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> RealmeView<'a> {
+    pub(crate) fn new(
+        realme: &'a Realme,
+        path: String,
+        root: Option<&'a Value>,
+    ) -> Self {
+        Self {
+            realme,
+            path,
+            root: root.unwrap_or(&NULL),
+        }
+    }
+
+    /// Returns the path of `key` in the realme the view belongs to.
+    fn path_of(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{key}", self.path)
+        }
+    }
+
+    /// Redacts an error about the value at `key` if it is sensitive in the
+    /// realme.
+    fn redact_error(&self, key: &str, error: Error) -> Error {
+        self.realme.redact_error(&self.path_of(key), error)
+    }
+
+    /// Retrieves a reference to the `Value` at the given key, relative to the
+    /// root of the view.
+    pub fn get<K: AsRef<str>>(&self, key: K) -> Option<&'a Value> {
+        self.root.get(key.as_ref())
+    }
+
+    /// Retrieves and deserializes the `Value` at the given key, relative to
+    /// the root of the view.
+    ///
+    /// Returns `None` if the key doesn't exist or deserialization fails.
+    pub fn get_as<V, K: AsRef<str>>(&self, key: K) -> Option<V>
+    where
+        V: DeserializeOwned,
+    {
+        self.get(key).and_then(|v| v.clone().try_deserialize().ok())
+    }
+
+    /// Retrieves and deserializes the `Value` at the given key, relative to
+    /// the root of the view.
+    ///
+    /// Returns `Ok(None)` if the key doesn't exist, or an error naming the
+    /// key if it is invalid or the value can't be deserialized.
+    pub fn try_get<V, K: AsRef<str>>(&self, key: K) -> Result<Option<V>>
+    where
+        V: DeserializeOwned,
+    {
+        self.root
+            .try_get(key.as_ref())
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Like `try_get`, but fails with `Error::MissingKey` if the key doesn't
+    /// exist.
+    pub fn require<V, K: AsRef<str>>(&self, key: K) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        self.root
+            .require(key.as_ref())
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Like `try_get`, but returns `default` if the key doesn't exist.
+    pub fn get_or<V, K: AsRef<str>>(&self, key: K, default: V) -> Result<V>
+    where
+        V: DeserializeOwned,
+    {
+        self.root
+            .get_or(key.as_ref(), default)
+            .map_err(|e| self.redact_error(key.as_ref(), e))
+    }
+
+    /// Returns a view rooted at the given key, relative to the root of this
+    /// view.
+    #[must_use]
+    pub fn sub<K: AsRef<str>>(&self, key: K) -> Self {
+        let key = key.as_ref();
+        Self::new(self.realme, self.path_of(key), self.get(key))
+    }
+
+    /// Returns the value at the root of the view, or `Value::Null` if the key
+    /// it was created for doesn't exist.
+    pub const fn value(&self) -> &'a Value {
+        self.root
+    }
+
+    /// Attempts to deserialize the value at the root of the view into a
+    /// specified type.
+    ///
+    /// Like [`Realme::try_deserialize`], an error that would quote a
+    /// sensitive value is replaced with `Error::Redacted`.
+    pub fn try_deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        self.root
+            .clone()
+            .try_deserialize()
+            .map_err(|e| self.realme.redact_quoted(e))
+    }
+}
+
+impl Realme {
+    /// Returns a read-only view of the configuration rooted at the given key.
+    ///
+    /// Keys passed to the view's `get`, `get_as` and `sub` are relative to
+    /// `key`, and its `try_deserialize` deserializes just that part of the
+    /// tree. A view of a missing key is rooted at `Value::Null`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::prelude::*;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct DatabaseConfig {
+    ///     url: String,
+    /// }
+    ///
+    /// let mut realme = Realme::builder().build().expect("build config");
+    /// realme
+    ///     .set("database.url", "postgres://localhost/mydb")
+    ///     .expect("set config");
+    ///
+    /// let database = realme.sub("database");
+    /// let config: DatabaseConfig =
+    ///     database.try_deserialize().expect("deserialize config");
+    /// assert_eq!(config.url, "postgres://localhost/mydb");
+    /// ```
+    pub fn sub<K: AsRef<str>>(&self, key: K) -> RealmeView<'_> {
+        let key = key.as_ref();
+        RealmeView::new(self, key.to_string(), self.get(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Pool {
+        size:    u32,
+        timeout: u32,
+    }
+
+    #[test]
+    fn test_sub() -> anyhow::Result<()> {
+        let mut realme = Realme::builder().build()?;
+        realme.set("database.url", "postgres://localhost/mydb")?;
+        realme.set("database.pool.size", 8)?;
+        realme.set("database.pool.timeout", 30)?;
+
+        let database = realme.sub("database");
+        assert_eq!(
+            database.get("url"),
+            Some(&Value::String("postgres://localhost/mydb".to_string()))
+        );
+        assert_eq!(database.get_as::<u32, _>("pool.size"), Some(8));
+        assert_eq!(database.require::<u32, _>("pool.timeout")?, 30);
+        assert_eq!(database.get_or("pool.idle", 5_u32)?, 5);
+        assert!(database.try_get::<u32, _>("url").is_err());
+        assert_eq!(database.sub("pool").try_deserialize::<Pool>()?, Pool {
+            size:    8,
+            timeout: 30,
+        });
+        assert_eq!(
+            realme.sub("database.pool").value(),
+            database.sub("pool").value()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_missing_key() -> anyhow::Result<()> {
+        let realme = Realme::builder().build()?;
+        let missing = realme.sub("missing");
+        assert_eq!(missing.value(), &Value::Null);
+        assert_eq!(missing.get("key"), None);
+        assert!(missing.try_deserialize::<Pool>().is_err());
+        assert_eq!(missing.try_deserialize::<Option<Pool>>()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_redacts_sensitive_values() -> anyhow::Result<()> {
+        // Unknown variant errors quote the value.
+        #[derive(Debug, Deserialize)]
+        enum Password {
+            Plain,
+            Hashed,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Database {
+            password: Password,
+        }
+
+        let mut realme =
+            Realme::builder().sensitive("database.password").build()?;
+        realme.set("database.password", "hunter2")?;
+        let database = realme.sub("database");
+
+        let errors = [
+            database.try_get::<u32, _>("password").map(|_| ()),
+            database.require::<u32, _>("password").map(|_| ()),
+            database.get_or("password", 0_u32).map(|_| ()),
+        ];
+        for err in errors {
+            let err = err.expect_err("not a number");
+            assert!(matches!(
+                &err,
+                Error::InvalidValue { key, source }
+                    if key == "password" && matches!(**source, Error::Redacted)
+            ));
+        }
+        let err = database
+            .try_deserialize::<Database>()
+            .expect_err("unknown variant");
+        assert!(matches!(err, Error::Redacted), "{err}");
+        let err = realme
+            .try_deserialize::<std::collections::HashMap<String, Database>>()
+            .expect_err("unknown variant");
+        assert!(matches!(err, Error::Redacted), "{err}");
+
+        realme.set("database.user", "admin")?;
+        assert!(matches!(
+            realme.sub("database").try_get::<Password, _>("user"),
+            Err(Error::InvalidValue { source, .. })
+                if !matches!(*source, Error::Redacted)
+        ));
+        Ok(())
+    }
+}
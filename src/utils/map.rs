@@ -10,6 +10,10 @@ use std::{
 type InnerMap<K, V> = HashMap<K, V>;
 type InnerEntry<'a, K, V> = Entry<'a, K, V>;
 
+/// The map behind `Value::Table`.
+///
+/// Iteration order is unspecified; sort the entries if you need a stable
+/// order.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Map<K, V>
 where
@@ -22,19 +26,22 @@ impl<K, V> Map<K, V>
 where
     K: Hash + Eq,
 {
-    pub(crate) fn new() -> Self {
+    /// Creates an empty map.
+    pub fn new() -> Self {
         Self {
             inner: InnerMap::new(),
         }
     }
 
+    /// Inserts a value, returning the one previously stored under `k`.
     #[inline]
-    pub(crate) fn insert(&mut self, k: K, v: V) -> Option<V> {
+    pub fn insert(&mut self, k: K, v: V) -> Option<V> {
         self.inner.insert(k, v)
     }
 
+    /// Returns a reference to the value stored under `k`.
     #[inline]
-    pub(crate) fn get<Q>(&self, k: &Q) -> Option<&V>
+    pub fn get<Q>(&self, k: &Q) -> Option<&V>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
@@ -42,7 +49,8 @@ where
         self.inner.get(k)
     }
 
-    pub(crate) fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
+    /// Returns a mutable reference to the value stored under `k`.
+    pub fn get_mut<Q>(&mut self, k: &Q) -> Option<&mut V>
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
@@ -50,11 +58,27 @@ where
         self.inner.get_mut(k)
     }
 
-    pub(crate) fn len(&self) -> usize {
+    /// Removes and returns the value stored under `k`.
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.inner.remove(k)
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
         self.inner.len()
     }
 
-    pub(crate) fn contains_key<Q>(&self, k: &Q) -> bool
+    /// Returns whether the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns whether a value is stored under `k`.
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
     where
         K: Borrow<Q>,
         Q: Hash + Eq + ?Sized,
@@ -66,9 +90,25 @@ where
         self.inner.entry(k)
     }
 
-    pub(crate) fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
+    /// Iterates over the entries.
+    pub fn iter(&self) -> <&Self as IntoIterator>::IntoIter {
         <&Self as IntoIterator>::into_iter(self)
     }
+
+    /// Iterates over the entries, with mutable references to the values.
+    pub fn iter_mut(&mut self) -> <&mut Self as IntoIterator>::IntoIter {
+        <&mut Self as IntoIterator>::into_iter(self)
+    }
+
+    /// Iterates over the keys.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.inner.keys()
+    }
+
+    /// Iterates over the values.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.inner.values()
+    }
 }
 
 impl<K, V> FromIterator<(K, V)> for Map<K, V>
@@ -108,3 +148,15 @@ where
         self.inner.iter()
     }
 }
+
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V>
+where
+    K: Hash + Eq,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = <&'a mut InnerMap<K, V> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.iter_mut()
+    }
+}
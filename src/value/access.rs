@@ -1,4 +1,16 @@
 mod expr;
+mod flatten;
 mod get;
 mod key;
+mod patch;
+mod query;
 mod set;
+
+pub use patch::PatchOperation;
+pub use query::Query;
+pub use set::{
+    Change,
+    MergeConflict,
+    MergeOptions,
+    MergeStrategy,
+};
@@ -1,8 +1,12 @@
-use std::str::FromStr;
+use std::{
+    borrow::Cow,
+    str::FromStr,
+};
 
 use crate::{
     Error,
     Result,
+    errors::SyntaxError,
 };
 
 /// Represents an expression in a custom language.
@@ -14,15 +18,18 @@ pub enum Expression {
     /// A simple identifier, e.g., `variable`.
     Identifier(String),
     /// A nested expression, e.g., `parent.child`.
-    Child(Vec<Expression>),
+    Child(Vec<Self>),
     /// An indexed access into an identifier, e.g., `array[2]`.
     Subscript(String, isize),
+    /// A new element appended to an array, e.g., `array[+]`. Only meaningful
+    /// when setting values.
+    Push(String),
 }
 
 impl std::fmt::Display for Expression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Identifier(id) => f.write_str(id),
+            Self::Identifier(id) => f.write_str(&quote_key(id)),
             Self::Child(exprs) => {
                 let mut iter = exprs.iter();
                 if let Some(first) = iter.next() {
@@ -34,7 +41,10 @@ impl std::fmt::Display for Expression {
                 }
                 Ok(())
             }
-            Self::Subscript(id, index) => write!(f, "{id}[{index}]"),
+            Self::Subscript(id, index) => {
+                write!(f, "{}[{index}]", quote_key(id))
+            }
+            Self::Push(id) => write!(f, "{}[+]", quote_key(id)),
         }
     }
 }
@@ -42,52 +52,152 @@ impl std::fmt::Display for Expression {
 impl FromStr for Expression {
     type Err = Error;
 
+    /// Parses a key such as `a.b[1].c`.
+    ///
+    /// Segments containing `.`, `[`, `]` or `"` can be written in double
+    /// quotes, either dotted (`hosts."example.com".port`) or in brackets
+    /// (`hosts["example.com"].port`). Inside quotes, `\"` and `\\` escape a
+    /// quote and a backslash.
     fn from_str(s: &str) -> Result<Self> {
-        let mut current = String::with_capacity(s.len());
-        let mut stack = Vec::with_capacity(s.len() / 2);
-        let mut sub_stack = Vec::with_capacity(2);
-
-        for (i, ch) in s.chars().enumerate() {
-            match ch {
-                '.' => {
-                    if !current.is_empty() {
-                        stack.push(Self::Identifier(current.clone()));
-                        current.clear();
-                    }
+        let mut segments: Vec<_> = parse_segments(s)?
+            .into_iter()
+            .map(|(expr, _)| expr)
+            .collect();
+        match segments.len() {
+            1 => Ok(segments.pop().expect("Failed to pop expression")),
+            _ => Ok(Self::Child(segments)),
+        }
+    }
+}
+
+/// Parses a key into its segments, each paired with whether its key was
+/// quoted. There is always at least one segment, and none is a `Child`.
+pub(super) fn parse_segments(s: &str) -> Result<Vec<(Expression, bool)>> {
+    let error = |source: SyntaxError| Error::ExprError {
+        expr: s.to_string(),
+        source,
+    };
+    let mut stack = Vec::with_capacity(s.len() / 2);
+    // The identifier read since the last `.`, if any, and whether it was
+    // quoted.
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    let mut rest = s;
+
+    while let Some(ch) = rest.chars().next() {
+        match ch {
+            '.' => {
+                if let Some(id) = current.take() {
+                    stack.push((Expression::Identifier(id), quoted));
                 }
-                '[' => {
-                    if !current.is_empty() {
-                        sub_stack.push(current.clone());
-                        current.clear();
-                    }
+                quoted = false;
+                rest = &rest[1..];
+            }
+            '"' => {
+                if current.is_some() {
+                    return Err(error(SyntaxError::Unexpected('"')));
                 }
-                ']' => {
-                    let identifier = sub_stack.pop().ok_or_else(|| {
-                        Error::ExprError("Unmatched ']' found".to_string())
-                    })?;
-                    let index = current.parse::<isize>().map_err(|e| {
-                        Error::ExprError(format!(
-                            "Invalid number format for subscript: {e}"
-                        ))
-                    })?;
-                    stack.push(Self::Subscript(identifier, index));
-                    current.clear();
+                let (key, tail) = parse_quoted(rest).map_err(error)?;
+                if !tail.is_empty() && !tail.starts_with(['.', '[']) {
+                    return Err(error(SyntaxError::ExpectedSeparator));
                 }
-                _ => current.push(ch),
+                current = Some(key);
+                quoted = true;
+                rest = tail;
             }
-
-            // Handle the case where ']' is the last character
-            if i == s.len() - 1 && !current.is_empty() {
-                stack.push(Self::Identifier(current.clone()));
+            '[' if rest[1..].starts_with('"') => {
+                let (key, tail) = parse_quoted(&rest[1..]).map_err(error)?;
+                rest = tail
+                    .strip_prefix(']')
+                    .ok_or_else(|| error(SyntaxError::Unmatched('[')))?;
+                if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+                    return Err(error(SyntaxError::ExpectedSeparator));
+                }
+                if let Some(id) = current.take() {
+                    stack.push((Expression::Identifier(id), quoted));
+                }
+                // Kept as the current key, so a subscript can follow, as in
+                // `hosts["example.com"][0]`.
+                current = Some(key);
+                quoted = true;
+            }
+            '[' => {
+                let (index, tail) = rest[1..]
+                    .split_once(']')
+                    .ok_or_else(|| error(SyntaxError::Unmatched('[')))?;
+                let identifier = current
+                    .take()
+                    .ok_or_else(|| error(SyntaxError::SubscriptWithoutKey))?;
+                rest = tail;
+                if index == "+" {
+                    stack.push((Expression::Push(identifier), quoted));
+                    continue;
+                }
+                let index = index.parse::<isize>().map_err(|_e| {
+                    error(SyntaxError::InvalidSubscript(index.to_string()))
+                })?;
+                stack.push((Expression::Subscript(identifier, index), quoted));
+            }
+            ']' => {
+                return Err(error(SyntaxError::Unmatched(']')));
             }
+            _ => {
+                let end = rest.find(['.', '[', ']', '"']).unwrap_or(rest.len());
+                current.get_or_insert_default().push_str(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+    }
+    if let Some(id) = current {
+        stack.push((Expression::Identifier(id), quoted));
+    }
+
+    if stack.is_empty() {
+        return Err(error(SyntaxError::Empty));
+    }
+    Ok(stack)
+}
+
+/// Quotes `key` if it can't be written as a bare segment, so it parses back
+/// to the same key. `*` is quoted too, since a query reads it as a wildcard;
+/// keys with `..` are already quoted for their `.`.
+pub(super) fn quote_key(key: &str) -> Cow<'_, str> {
+    if !key.is_empty() &&
+        key != "*" &&
+        !key.contains(['.', '[', ']', '"', '\\'])
+    {
+        return Cow::Borrowed(key);
+    }
+    let mut quoted = String::with_capacity(key.len() + 2);
+    quoted.push('"');
+    for ch in key.chars() {
+        if matches!(ch, '"' | '\\') {
+            quoted.push('\\');
         }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
 
-        match stack.len() {
-            0 => Err(Error::ExprError("Empty expression".to_string())),
-            1 => Ok(stack.pop().expect("Failed to pop expression")),
-            _ => Ok(Self::Child(stack)),
+/// Reads a double-quoted key from the start of `s`, returning the unescaped
+/// key and the rest of `s`, or why the key is invalid.
+pub(super) fn parse_quoted(
+    s: &str,
+) -> std::result::Result<(String, &str), SyntaxError> {
+    let mut key = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '"' => return Ok((key, &s[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('"' | '\\'))) => key.push(escaped),
+                _ => return Err(SyntaxError::InvalidEscape),
+            },
+            _ => key.push(ch),
         }
     }
+    Err(SyntaxError::UnterminatedQuote)
 }
 
 #[cfg(test)]
@@ -165,4 +275,71 @@ mod tests {
         assert_eq!(parsed, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_quoted() -> anyhow::Result<()> {
+        let expected = Expression::Child(vec![
+            Expression::Identifier("hosts".into()),
+            Expression::Identifier("example.com".into()),
+            Expression::Identifier("port".into()),
+        ]);
+        assert_eq!(
+            r#"hosts."example.com".port"#.parse::<Expression>()?,
+            expected
+        );
+        assert_eq!(
+            r#"hosts["example.com"].port"#.parse::<Expression>()?,
+            expected
+        );
+        assert_eq!(
+            r#""a.b"[2]"#.parse::<Expression>()?,
+            Expression::Subscript("a.b".into(), 2)
+        );
+        assert_eq!(
+            r#"a["say \"hi\""]"#.parse::<Expression>()?,
+            Expression::Child(vec![
+                Expression::Identifier("a".into()),
+                Expression::Identifier(r#"say "hi""#.into()),
+            ])
+        );
+        assert_eq!(
+            r#"hosts["example.com"][0]"#.parse::<Expression>()?,
+            Expression::Child(vec![
+                Expression::Identifier("hosts".into()),
+                Expression::Subscript("example.com".into(), 0),
+            ])
+        );
+        assert_eq!(
+            r#"a["b"]["c"]"#.parse::<Expression>()?,
+            Expression::Child(vec![
+                Expression::Identifier("a".into()),
+                Expression::Identifier("b".into()),
+                Expression::Identifier("c".into()),
+            ])
+        );
+        assert!(r#"a."b"#.parse::<Expression>().is_err());
+        assert!(r#"a."b"c"#.parse::<Expression>().is_err());
+        assert!(r#"a["b"#.parse::<Expression>().is_err());
+        assert!(r#"a["b"]c"#.parse::<Expression>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_display_round_trip() -> anyhow::Result<()> {
+        for key in [
+            "a.b[1].c",
+            r#"hosts."example.com".port"#,
+            r#""a.b"[-1]"#,
+            r#"a."say \"hi\"".b"#,
+            r#"a."""#,
+            r#"a."*".b"#,
+            r#"a.".."[0]"#,
+            "hosts[+].name",
+        ] {
+            let expr: Expression = key.parse()?;
+            assert_eq!(expr.to_string(), key);
+            assert_eq!(expr.to_string().parse::<Expression>()?, expr);
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,380 @@
+use std::borrow::Cow;
+
+use super::{
+    expr::{
+        Expression,
+        parse_segments,
+        quote_key,
+    },
+    get::Key,
+    set::type_mismatch,
+};
+use crate::{
+    Error,
+    Map,
+    Result,
+    Value,
+    errors::AccessError,
+};
+
+impl Value {
+    /// Flattens the value into `(path, leaf)` pairs, sorted by path.
+    ///
+    /// Paths use the same syntax as `get`: table keys are joined with `.` and
+    /// array elements are subscripted, e.g. `servers[0].host`. Elements of an
+    /// array nested in another array are addressed with `.`, as in
+    /// `matrix[0].1`, so numeric table keys are quoted, as in `ports."80"`.
+    /// Empty tables and arrays count as leaves, so [`Value::unflatten`] gives
+    /// back the same value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value
+    ///     .set("server.port", Value::Integer(8080))
+    ///     .expect("set port");
+    /// value
+    ///     .set(
+    ///         "server.hosts",
+    ///         Value::Array(vec![Value::String("localhost".to_string())]),
+    ///     )
+    ///     .expect("set hosts");
+    ///
+    /// let flat = value.flatten();
+    /// assert_eq!(flat, vec![
+    ///     (
+    ///         "server.hosts[0]".to_string(),
+    ///         &Value::String("localhost".to_string())
+    ///     ),
+    ///     ("server.port".to_string(), &Value::Integer(8080)),
+    /// ]);
+    ///
+    /// let pairs = flat.into_iter().map(|(path, leaf)| (path, leaf.clone()));
+    /// assert_eq!(Value::unflatten(pairs).expect("unflatten"), value);
+    /// ```
+    pub fn flatten(&self) -> Vec<(String, &Self)> {
+        let mut leaves = Vec::new();
+        for_each_leaf(String::new(), self, &mut |path, leaf| {
+            leaves.push((path, leaf));
+        });
+        leaves
+    }
+
+    /// Returns the path of every leaf, in the same order as
+    /// [`Value::flatten`].
+    pub fn keys(&self) -> Vec<String> {
+        self.flatten().into_iter().map(|(path, _)| path).collect()
+    }
+
+    /// Returns whether a value exists at `key`.
+    pub fn contains<K: Key>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Builds a table from `(path, value)` pairs, as produced by
+    /// [`Value::flatten`].
+    ///
+    /// Subscripts create arrays, and each index must refer to an existing
+    /// element or to the end of the array, so elements have to come in order.
+    /// A path starting with an unquoted number indexes a root array, and the
+    /// empty path replaces the whole value. Pairs are applied in order, so a
+    /// later path overrides an earlier one. No pairs give an empty table.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a path can't be parsed, uses a negative subscript or one past
+    /// the end of its array, or goes through a value that is neither a table
+    /// nor an array.
+    pub fn unflatten<I, K, V>(pairs: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<Self>,
+    {
+        let mut root = None;
+        for (path, value) in pairs {
+            let root = root.get_or_insert(Self::Null);
+            let path = path.as_ref();
+            if path.is_empty() {
+                *root = value.into();
+                continue;
+            }
+            let mut steps = Vec::new();
+            for (expr, quoted) in parse_segments(path)? {
+                push_steps(expr, quoted, &mut steps);
+            }
+            let mut current = root;
+            // The root is read like an array element, as `flatten` writes
+            // the indexes of a root array as bare numbers.
+            let mut in_array = true;
+            for step in steps {
+                let is_index = !matches!(step, Step::Key(..));
+                current =
+                    step_into(current, step, in_array).map_err(|source| {
+                        Error::SetValueError {
+                            key: path.to_string(),
+                            source,
+                        }
+                    })?;
+                in_array = is_index;
+            }
+            *current = value.into();
+        }
+        Ok(root.unwrap_or_else(|| Self::Table(Map::new())))
+    }
+}
+
+/// One step of a path: a table key and whether it was quoted, an index into
+/// an array, or a new element at its end.
+enum Step {
+    Key(String, bool),
+    Index(isize),
+    Push,
+}
+
+/// Breaks a segment of a path into the steps it takes.
+fn push_steps(expr: Expression, quoted: bool, steps: &mut Vec<Step>) {
+    match expr {
+        Expression::Identifier(id) => steps.push(Step::Key(id, quoted)),
+        Expression::Subscript(id, idx) => {
+            steps.push(Step::Key(id, quoted));
+            steps.push(Step::Index(idx));
+        }
+        Expression::Push(id) => {
+            steps.push(Step::Key(id, quoted));
+            steps.push(Step::Push);
+        }
+        Expression::Child(exprs) => {
+            for expr in exprs {
+                push_steps(expr, quoted, steps);
+            }
+        }
+    }
+}
+
+/// Moves one step down from `current`, creating the table or array the step
+/// needs if `current` is null. An unquoted numeric key right below an array
+/// index creates an array, matching the paths `flatten` emits for nested
+/// arrays.
+fn step_into(
+    current: &mut Value,
+    step: Step,
+    in_array: bool,
+) -> std::result::Result<&mut Value, AccessError> {
+    let step = match step {
+        Step::Key(key, false)
+            if is_numeric(&key) &&
+                (in_array || matches!(current, Value::Array(_))) =>
+        {
+            key.parse().map_or(Step::Key(key, false), Step::Index)
+        }
+        step => step,
+    };
+    match step {
+        Step::Key(key, _) => {
+            if matches!(current, Value::Null) {
+                *current = Value::Table(Map::new());
+            }
+            match current {
+                Value::Table(table) => {
+                    Ok(table.entry(key).or_insert(Value::Null))
+                }
+                other => Err(type_mismatch("a table", other)),
+            }
+        }
+        Step::Index(_) | Step::Push => {
+            if matches!(current, Value::Null) {
+                *current = Value::Array(Vec::new());
+            }
+            let Value::Array(array) = current else {
+                return Err(type_mismatch("an array", current));
+            };
+            let len = array.len();
+            let idx = match step {
+                Step::Index(idx) => usize::try_from(idx)
+                    .ok()
+                    .filter(|i| *i <= len)
+                    .ok_or(AccessError::OutOfBounds { index: idx, len })?,
+                _ => len,
+            };
+            if idx == len {
+                array.push(Value::Null);
+            }
+            Ok(&mut array[idx])
+        }
+    }
+}
+
+/// Joins a table key onto a path, quoting it if needed. Numeric keys are
+/// quoted as well, so `unflatten` doesn't read them back as array indexes.
+pub(super) fn child_path(path: &str, key: &str) -> String {
+    let key = if is_numeric(key) {
+        Cow::Owned(format!("\"{key}\""))
+    } else {
+        quote_key(key)
+    };
+    if path.is_empty() {
+        key.into_owned()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+/// Whether `key` is made of decimal digits, like an array index.
+fn is_numeric(key: &str) -> bool {
+    !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Joins an array index onto a path. Indexes into the root or into another
+/// array's element are written as keys, since subscripts can't be chained.
+pub(super) fn index_path(path: &str, index: usize) -> String {
+    if path.is_empty() {
+        index.to_string()
+    } else if path.ends_with(']') {
+        format!("{path}.{index}")
+    } else {
+        format!("{path}[{index}]")
+    }
+}
+
+/// Calls `f` with every leaf below `value`, sorting table keys. Empty tables
+/// and arrays count as leaves.
+pub(super) fn for_each_leaf<'a, F>(path: String, value: &'a Value, f: &mut F)
+where
+    F: FnMut(String, &'a Value),
+{
+    match value {
+        Value::Table(table) if !table.is_empty() => {
+            let mut entries: Vec<_> = table.iter().collect();
+            entries.sort_by_key(|(k, _)| *k);
+            for (key, child) in entries {
+                for_each_leaf(child_path(&path, key), child, f);
+            }
+        }
+        Value::Array(array) if !array.is_empty() => {
+            for (i, child) in array.iter().enumerate() {
+                for_each_leaf(index_path(&path, i), child, f);
+            }
+        }
+        _ => f(path, value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value() -> Result<Value> {
+        let mut value = Value::Null;
+        value.set("server.host", Value::String("localhost".to_string()))?;
+        value.set(
+            "server.ports",
+            Value::Array(vec![Value::Integer(80), Value::Integer(443)]),
+        )?;
+        value.set(
+            "matrix",
+            Value::Array(vec![Value::Array(vec![
+                Value::Integer(1),
+                Value::Array(vec![Value::Integer(2)]),
+            ])]),
+        )?;
+        value.set("empty", Value::Table(Map::new()))?;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_flatten() -> anyhow::Result<()> {
+        let value = value()?;
+        assert_eq!(value.keys(), vec![
+            "empty",
+            "matrix[0].0",
+            "matrix[0].1[0]",
+            "server.host",
+            "server.ports[0]",
+            "server.ports[1]",
+        ]);
+        for (path, leaf) in value.flatten() {
+            assert_eq!(value.get(path.as_str()), Some(leaf), "{path}");
+        }
+        assert!(value.contains("server.ports[1]"));
+        assert!(!value.contains("server.ports[2]"));
+        assert_eq!(Value::Integer(1).keys(), vec![""]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unflatten() -> anyhow::Result<()> {
+        let value = value()?;
+        let pairs = value
+            .flatten()
+            .into_iter()
+            .map(|(path, leaf)| (path, leaf.clone()));
+        assert_eq!(Value::unflatten(pairs)?, value);
+
+        let list = Value::unflatten([
+            ("list[0]", Value::Integer(1)),
+            ("list[1]", Value::Integer(2)),
+            ("list[0]", Value::Integer(3)),
+        ])?;
+        assert_eq!(
+            list.get("list"),
+            Some(&Value::Array(vec![Value::Integer(3), Value::Integer(2)]))
+        );
+        assert!(Value::unflatten([("list[2]", Value::Null)]).is_err());
+        assert!(
+            Value::unflatten([("list[9223372036854775807]", Value::Null)])
+                .is_err()
+        );
+        assert!(Value::unflatten([("list[-1]", Value::Null)]).is_err());
+        assert!(
+            Value::unflatten([
+                ("a", Value::Integer(1)),
+                ("a.b", Value::Integer(2))
+            ])
+            .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unflatten_round_trip() -> anyhow::Result<()> {
+        let mut numeric = Map::new();
+        numeric.insert("0".to_string(), Value::String("x".to_string()));
+        let values = [
+            Value::Array(vec![Value::Table(numeric.clone())]),
+            Value::Table(
+                [("list".to_string(), Value::Table(numeric))]
+                    .into_iter()
+                    .collect(),
+            ),
+            Value::Integer(1),
+            Value::Null,
+            Value::Array(Vec::new()),
+            Value::Table(Map::new()),
+        ];
+        for value in values {
+            let pairs = value
+                .flatten()
+                .into_iter()
+                .map(|(path, leaf)| (path, leaf.clone()));
+            assert_eq!(Value::unflatten(pairs)?, value, "{value:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_flatten_quoted_keys() -> anyhow::Result<()> {
+        let mut value = Value::Null;
+        value.set(r#"hosts."example.com".port"#, Value::Integer(443))?;
+        assert_eq!(value.keys(), vec![r#"hosts."example.com".port"#]);
+        let pairs = value
+            .flatten()
+            .into_iter()
+            .map(|(path, leaf)| (path, leaf.clone()));
+        assert_eq!(Value::unflatten(pairs)?, value);
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 use serde::Deserialize;
 
-use super::expr::Expression;
+use super::{
+    expr::Expression,
+    set::resolve_index,
+};
 use crate::{
+    Error,
     Result,
     Value,
 };
@@ -115,6 +119,56 @@ impl Value {
             .and_then(|v| v.clone().try_deserialize().ok())
     }
 
+    /// Retrieves and deserializes the value at `key`.
+    ///
+    /// Unlike `get_as`, an invalid key or a value that can't be deserialized
+    /// into `T` is an error naming the key; only a missing key gives
+    /// `Ok(None)`.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn try_get<'de, K: Key, T: Deserialize<'de>>(
+        &'de self,
+        key: K,
+    ) -> Result<Option<T>> {
+        let expr = key.to_key().map_err(|e| Error::InvalidKey {
+            key:    key.into_string(),
+            source: Box::new(e),
+        })?;
+        self.get_internal(&expr)
+            .map(|v| {
+                v.clone()
+                    .try_deserialize()
+                    .map_err(|e| Error::InvalidValue {
+                        key:    key.into_string(),
+                        source: Box::new(e),
+                    })
+            })
+            .transpose()
+    }
+
+    /// Retrieves and deserializes the value at `key`, failing with
+    /// `Error::MissingKey` if it doesn't exist.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn require<'de, K: Key, T: Deserialize<'de>>(
+        &'de self,
+        key: K,
+    ) -> Result<T> {
+        self.try_get(key.clone())?
+            .ok_or_else(|| Error::MissingKey(key.into_string()))
+    }
+
+    /// Retrieves and deserializes the value at `key`, or returns `default` if
+    /// it doesn't exist.
+    ///
+    /// A value that exists but can't be deserialized into `T` is still an
+    /// error.
+    pub fn get_or<'de, K: Key, T: Deserialize<'de>>(
+        &'de self,
+        key: K,
+        default: T,
+    ) -> Result<T> {
+        Ok(self.try_get(key)?.unwrap_or(default))
+    }
+
     fn get_internal<'a>(&'a self, key: &Expression) -> Option<&'a Self> {
         match key {
             Expression::Identifier(id) => match self {
@@ -127,16 +181,12 @@ impl Value {
                     self.get_internal(&Expression::Identifier(id.clone()))?;
                 match v {
                     Self::Array(arr) => {
-                        let index = if *idx >= 0 {
-                            *idx as usize
-                        } else {
-                            arr.len().wrapping_add(*idx as usize)
-                        };
-                        arr.get(index).or(None)
+                        arr.get(resolve_index(*idx, arr.len())?)
                     }
                     _ => None,
                 }
             }
+            Expression::Push(_) => None,
             Expression::Child(exprs) => exprs
                 .iter()
                 .try_fold(self, |acc, expr| acc.get_internal(expr)),
@@ -152,26 +202,17 @@ impl Value {
                 Self::Array(arr) => arr.get_mut(id.parse::<usize>().ok()?),
                 _ => None,
             },
-            Expression::Subscript(id, idx) => {
-                if let Self::Table(table) = self {
-                    let entry = table
-                        .entry(id.clone())
-                        .or_insert_with(|| Self::Array(Vec::new()));
-                    if let Self::Array(arr) = entry {
-                        let index = if *idx >= 0 {
-                            *idx as usize
-                        } else {
-                            arr.len().wrapping_add(*idx as usize)
-                        };
-                        // Ensure the array has enough capacity
-                        if index >= arr.len() {
-                            arr.resize(index + 1, Self::Null);
-                        }
-                        return Some(&mut arr[index]);
+            Expression::Subscript(id, idx) => match self {
+                Self::Table(table) => match table.get_mut(id) {
+                    Some(Self::Array(arr)) => {
+                        let index = resolve_index(*idx, arr.len())?;
+                        Some(&mut arr[index])
                     }
-                }
-                None
-            }
+                    _ => None,
+                },
+                _ => None,
+            },
+            Expression::Push(_) => None,
             Expression::Child(exprs) => {
                 let mut current = self;
                 for expr in exprs {
@@ -182,3 +223,86 @@ impl Value {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Map,
+        errors::SyntaxError,
+    };
+
+    fn value() -> Value {
+        let mut server = Map::new();
+        server.insert("host".to_string(), Value::String("localhost".into()));
+        server.insert("port".to_string(), Value::Integer(8080));
+        let mut table = Map::new();
+        table.insert("server".to_string(), Value::Table(server));
+        Value::Table(table)
+    }
+
+    #[test]
+    fn test_get_mut_subscript() {
+        let mut value = Value::Null;
+        value
+            .set(
+                "list",
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            )
+            .expect("set list");
+        if let Some(last) = value.get_mut("list[-1]") {
+            *last = Value::Integer(3);
+        }
+        assert_eq!(value.get("list[1]"), Some(&Value::Integer(3)));
+        assert_eq!(value.get_mut("list[-5]"), None);
+        assert_eq!(value.get_mut("list[9223372036854775807]"), None);
+        assert_eq!(value.get_mut("missing[0]"), None);
+        assert_eq!(value.get("list[-5]"), None);
+        assert_eq!(
+            value.get("list"),
+            Some(&Value::Array(vec![Value::Integer(1), Value::Integer(3)]))
+        );
+        assert_eq!(value.get("missing"), None);
+    }
+
+    #[test]
+    fn test_try_get() -> anyhow::Result<()> {
+        let value = value();
+        assert_eq!(value.try_get::<_, u16>("server.port")?, Some(8080));
+        assert_eq!(value.try_get::<_, u16>("server.missing")?, None);
+        assert!(matches!(
+            value.try_get::<_, u16>("server.host"),
+            Err(Error::InvalidValue { key, .. }) if key == "server.host"
+        ));
+        let err = value
+            .try_get::<_, u16>("server.port]")
+            .expect_err("invalid key");
+        assert!(matches!(
+            &err,
+            Error::InvalidKey { key, source } if key == "server.port]" &&
+                matches!(
+                    **source,
+                    Error::ExprError {
+                        source: SyntaxError::Unmatched(']'),
+                        ..
+                    }
+                )
+        ));
+        assert!(std::error::Error::source(&err).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_require_and_get_or() -> anyhow::Result<()> {
+        let value = value();
+        assert_eq!(value.require::<_, String>("server.host")?, "localhost");
+        assert!(matches!(
+            value.require::<_, String>("server.user"),
+            Err(Error::MissingKey(key)) if key == "server.user"
+        ));
+        assert_eq!(value.get_or("server.port", 80_u16)?, 8080);
+        assert_eq!(value.get_or("server.workers", 4_u16)?, 4);
+        assert!(value.get_or("server.host", 80_u16).is_err());
+        Ok(())
+    }
+}
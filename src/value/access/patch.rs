@@ -0,0 +1,639 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use super::{
+    expr::Expression,
+    set::type_mismatch,
+};
+use crate::{
+    Error,
+    Map,
+    Result,
+    Value,
+    errors::AccessError,
+};
+
+/// A single RFC 6902 JSON Patch operation.
+///
+/// Paths are either key expressions such as `server.ports[0]`, or JSON
+/// Pointers such as `/server/ports/0` when they start with `/`. The empty
+/// path refers to the whole value. In a JSON Pointer, array indices are
+/// non-negative decimal numbers and `-` refers to the end of an array, so
+/// `add` with `/ports/-` appends. Key expressions also accept negative
+/// indices counting from the end, such as `ports[-1]`.
+///
+/// Operations serialize the way RFC 6902 writes them, e.g.
+/// `{ "op": "replace", "path": "/port", "value": 8080 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Inserts `value` at `path`, replacing an existing table entry or
+    /// shifting array elements to the right. A negative subscript names the
+    /// element `value` is inserted before, so `ports[-1]` inserts before the
+    /// last port.
+    Add { path: String, value: Value },
+    /// Removes the value at `path`, which must exist.
+    Remove { path: String },
+    /// Replaces the value at `path`, which must exist.
+    Replace { path: String, value: Value },
+    /// Removes the value at `from` and adds it at `path`.
+    Move { from: String, path: String },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Fails unless the value at `path` equals `value`.
+    Test { path: String, value: Value },
+}
+
+impl Value {
+    /// Applies an RFC 7396 JSON Merge Patch.
+    ///
+    /// Tables in `patch` are merged recursively, `Null` entries remove the
+    /// corresponding key and any other value replaces the target.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut config = Value::Null;
+    /// config
+    ///     .set("server.host", Value::String("localhost".to_string()))
+    ///     .expect("set");
+    /// config
+    ///     .set("server.debug", Value::Boolean(true))
+    ///     .expect("set");
+    ///
+    /// let mut patch = Value::Null;
+    /// patch.set("server.debug", Value::Null).expect("set");
+    /// patch.set("server.port", Value::Integer(8080)).expect("set");
+    /// config.merge_patch(&patch);
+    ///
+    /// assert_eq!(config.get("server.debug"), None);
+    /// assert_eq!(config.get("server.port"), Some(&Value::Integer(8080)));
+    /// ```
+    pub fn merge_patch(&mut self, patch: &Self) {
+        let Self::Table(entries) = patch else {
+            *self = patch.clone();
+            return;
+        };
+        if !matches!(self, Self::Table(_)) {
+            *self = Self::Table(Map::new());
+        }
+        let Self::Table(table) = self else {
+            unreachable!("target was just turned into a table")
+        };
+        for (key, value) in entries {
+            if value == &Self::Null {
+                table.remove(key);
+            } else {
+                table
+                    .entry(key.clone())
+                    .or_insert(Self::Null)
+                    .merge_patch(value);
+            }
+        }
+    }
+
+    /// Applies a sequence of RFC 6902 JSON Patch operations.
+    ///
+    /// The operations are applied atomically: if any of them fails, the value
+    /// is left unchanged and the error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::{
+    ///     PatchOperation,
+    ///     Value,
+    /// };
+    ///
+    /// let mut config = Value::Null;
+    /// config
+    ///     .set("server.port", Value::Integer(8080))
+    ///     .expect("set");
+    ///
+    /// config
+    ///     .apply_patch(&[
+    ///         PatchOperation::Test {
+    ///             path:  "server.port".to_string(),
+    ///             value: Value::Integer(8080),
+    ///         },
+    ///         PatchOperation::Move {
+    ///             from: "server.port".to_string(),
+    ///             path: "/server/listen".to_string(),
+    ///         },
+    ///     ])
+    ///     .expect("apply patch");
+    ///
+    /// assert_eq!(config.get("server.listen"), Some(&Value::Integer(8080)));
+    /// ```
+    pub fn apply_patch(&mut self, operations: &[PatchOperation]) -> Result<()> {
+        let mut patched = self.clone();
+        for operation in operations {
+            patched.apply_operation(operation)?;
+        }
+        *self = patched;
+        Ok(())
+    }
+
+    fn apply_operation(&mut self, operation: &PatchOperation) -> Result<()> {
+        match operation {
+            PatchOperation::Add { path, value } => {
+                self.patch_add(path, &segments(path)?, value.clone())
+            }
+            PatchOperation::Remove { path } => {
+                self.patch_remove(path, &segments(path)?).map(|_| ())
+            }
+            PatchOperation::Replace { path, value } => {
+                let target = self
+                    .patch_get_mut(&segments(path)?)
+                    .ok_or_else(|| patch_error(path, AccessError::NotFound))?;
+                *target = value.clone();
+                Ok(())
+            }
+            PatchOperation::Move { from, path } => {
+                let source = segments(from)?;
+                let target = segments(path)?;
+                let (source_keys, target_keys) =
+                    (self.resolve_keys(&source), self.resolve_keys(&target));
+                if target_keys.len() > source_keys.len() &&
+                    target_keys.starts_with(&source_keys)
+                {
+                    return Err(patch_error(
+                        path,
+                        AccessError::MoveIntoChild(from.clone()),
+                    ));
+                }
+                let value = self.patch_remove(from, &source)?;
+                self.patch_add(path, &target, value)
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = self
+                    .patch_get(&segments(from)?)
+                    .cloned()
+                    .ok_or_else(|| patch_error(from, AccessError::NotFound))?;
+                self.patch_add(path, &segments(path)?, value)
+            }
+            PatchOperation::Test { path, value } => {
+                match self.patch_get(&segments(path)?) {
+                    Some(current) if current == value => Ok(()),
+                    Some(current) => {
+                        Err(patch_error(path, AccessError::TestFailed {
+                            expected: Box::new(value.clone()),
+                            found:    Box::new(current.clone()),
+                        }))
+                    }
+                    None => Err(patch_error(path, AccessError::NotFound)),
+                }
+            }
+        }
+    }
+
+    fn patch_get(&self, segments: &[Segment]) -> Option<&Self> {
+        segments
+            .iter()
+            .try_fold(self, |current, segment| match current {
+                Self::Table(table) => table.get(segment.key().as_ref()),
+                Self::Array(arr) => {
+                    arr.get(array_index(segment, arr.len()).ok()?)
+                }
+                _ => None,
+            })
+    }
+
+    fn patch_get_mut(&mut self, segments: &[Segment]) -> Option<&mut Self> {
+        segments
+            .iter()
+            .try_fold(self, |current, segment| match current {
+                Self::Table(table) => table.get_mut(segment.key().as_ref()),
+                Self::Array(arr) => {
+                    let index = array_index(segment, arr.len()).ok()?;
+                    arr.get_mut(index)
+                }
+                _ => None,
+            })
+    }
+
+    /// Names each step of `segments` the same way, whether it comes from a
+    /// JSON Pointer or a key expression, resolving negative subscripts
+    /// against the arrays of `self` they index.
+    fn resolve_keys(&self, segments: &[Segment]) -> Vec<String> {
+        let mut current = Some(self);
+        segments
+            .iter()
+            .map(|segment| {
+                let key = match current {
+                    Some(Self::Array(arr)) => array_index(segment, arr.len())
+                        .map_or_else(
+                            |_| segment.to_string(),
+                            |i| i.to_string(),
+                        ),
+                    _ => segment.to_string(),
+                };
+                current = current
+                    .and_then(|c| c.patch_get(std::slice::from_ref(segment)));
+                key
+            })
+            .collect()
+    }
+
+    fn patch_add(
+        &mut self,
+        path: &str,
+        segments: &[Segment],
+        value: Self,
+    ) -> Result<()> {
+        let Some((last, parent)) = segments.split_last() else {
+            *self = value;
+            return Ok(());
+        };
+        match self.patch_get_mut(parent) {
+            Some(Self::Table(table)) => {
+                table.insert(last.key().into_owned(), value);
+                Ok(())
+            }
+            Some(Self::Array(arr)) => {
+                let index = match last {
+                    _ if last.is_end() => Ok(arr.len()),
+                    // Like a negative subscript anywhere else, it names an
+                    // existing element, before which the value goes.
+                    Segment::Index(index) if *index < 0 => {
+                        array_index(last, arr.len())
+                    }
+                    _ => array_index(last, arr.len() + 1),
+                }
+                .map_err(|cause| patch_error(path, cause))?;
+                arr.insert(index, value);
+                Ok(())
+            }
+            Some(other) => Err(patch_error(
+                path,
+                type_mismatch("a table or an array", other),
+            )),
+            None => Err(patch_error(path, AccessError::ParentNotFound)),
+        }
+    }
+
+    fn patch_remove(
+        &mut self,
+        path: &str,
+        segments: &[Segment],
+    ) -> Result<Self> {
+        let Some((last, parent)) = segments.split_last() else {
+            return Ok(std::mem::take(self));
+        };
+        let removed = match self.patch_get_mut(parent) {
+            Some(Self::Table(table)) => table.remove(last.key().as_ref()),
+            Some(Self::Array(arr)) => array_index(last, arr.len())
+                .ok()
+                .map(|index| arr.remove(index)),
+            _ => None,
+        };
+        removed.ok_or_else(|| patch_error(path, AccessError::NotFound))
+    }
+}
+
+fn patch_error(path: &str, source: AccessError) -> Error {
+    Error::PatchError {
+        path: path.to_string(),
+        source,
+    }
+}
+
+/// One step of a patch path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// A JSON Pointer reference token, or a key of a key expression. On an
+    /// array it must be a non-negative decimal index, or `-` for the end.
+    Token(String),
+    /// A subscript of a key expression, counting from the end if negative.
+    Index(isize),
+}
+
+impl Segment {
+    /// The segment as a table key.
+    fn key(&self) -> Cow<'_, str> {
+        match self {
+            Self::Token(token) => Cow::Borrowed(token),
+            Self::Index(index) => Cow::Owned(index.to_string()),
+        }
+    }
+
+    /// Whether the segment refers to the end of an array.
+    fn is_end(&self) -> bool {
+        matches!(self, Self::Token(token) if token == "-")
+    }
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.key())
+    }
+}
+
+/// Splits a patch path into its segments. Paths starting with `/` are JSON
+/// Pointers (RFC 6901), anything else is a key expression.
+fn segments(path: &str) -> Result<Vec<Segment>> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Some(pointer) = path.strip_prefix('/') {
+        return Ok(pointer
+            .split('/')
+            .map(|token| {
+                Segment::Token(token.replace("~1", "/").replace("~0", "~"))
+            })
+            .collect());
+    }
+    let mut segments = Vec::new();
+    push_segments(&path.parse()?, &mut segments);
+    Ok(segments)
+}
+
+fn push_segments(expr: &Expression, segments: &mut Vec<Segment>) {
+    match expr {
+        Expression::Identifier(id) => segments.push(Segment::Token(id.clone())),
+        Expression::Subscript(id, index) => {
+            segments.push(Segment::Token(id.clone()));
+            segments.push(Segment::Index(*index));
+        }
+        Expression::Push(id) => {
+            segments.push(Segment::Token(id.clone()));
+            segments.push(Segment::Token("-".to_string()));
+        }
+        Expression::Child(exprs) => {
+            for expr in exprs {
+                push_segments(expr, segments);
+            }
+        }
+    }
+}
+
+/// Resolves an array index below `len`. Only subscripts of key expressions
+/// may be negative, counting from the end.
+fn array_index(
+    segment: &Segment,
+    len: usize,
+) -> std::result::Result<usize, AccessError> {
+    let index = match segment {
+        Segment::Index(index) if *index < 0 => {
+            len.checked_sub(index.unsigned_abs())
+        }
+        Segment::Index(index) => Some(index.cast_unsigned()),
+        // RFC 6901 indices are decimal digits, without leading zeros.
+        Segment::Token(token)
+            if token.bytes().all(|b| b.is_ascii_digit()) &&
+                (token == "0" ||
+                    !token.is_empty() && !token.starts_with('0')) =>
+        {
+            token.parse::<usize>().ok()
+        }
+        Segment::Token(token) => {
+            return Err(AccessError::InvalidIndex(token.clone()));
+        }
+    };
+    index.filter(|i| *i < len).ok_or_else(|| {
+        let index = match segment {
+            Segment::Index(index) => Some(*index),
+            Segment::Token(token) => token.parse().ok(),
+        };
+        index.map_or_else(
+            || AccessError::InvalidIndex(segment.to_string()),
+            |index| AccessError::OutOfBounds { index, len },
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Value {
+        let mut value = Value::Table(Map::new());
+        value
+            .set("server.host", Value::String("localhost".to_string()))
+            .expect("set");
+        value.set("server.port", Value::Integer(8080)).expect("set");
+        value
+            .set(
+                "ports",
+                Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            )
+            .expect("set");
+        value
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut value = config();
+        let mut patch = Value::Table(Map::new());
+        patch.set("server.host", Value::Null).expect("set");
+        patch
+            .set("server.tls.enabled", Value::Boolean(true))
+            .expect("set");
+        patch.set("ports", Value::Integer(3)).expect("set");
+        value.merge_patch(&patch);
+
+        assert_eq!(value.get("server.host"), None);
+        assert_eq!(value.get("server.port"), Some(&Value::Integer(8080)));
+        assert_eq!(
+            value.get("server.tls.enabled"),
+            Some(&Value::Boolean(true))
+        );
+        assert_eq!(value.get("ports"), Some(&Value::Integer(3)));
+
+        let mut value = Value::Integer(1);
+        value.merge_patch(&patch);
+        assert_eq!(value.get("server.host"), None);
+        assert_eq!(value.get("ports"), Some(&Value::Integer(3)));
+    }
+
+    #[test]
+    fn test_apply_patch() -> anyhow::Result<()> {
+        let mut value = config();
+        value.apply_patch(&[
+            PatchOperation::Add {
+                path:  "/ports/-".to_string(),
+                value: Value::Integer(3),
+            },
+            PatchOperation::Add {
+                path:  "ports[0]".to_string(),
+                value: Value::Integer(0),
+            },
+            PatchOperation::Remove {
+                path: "server.host".to_string(),
+            },
+            PatchOperation::Replace {
+                path:  "/server/port".to_string(),
+                value: Value::Integer(9090),
+            },
+            PatchOperation::Copy {
+                from: "server".to_string(),
+                path: "backup".to_string(),
+            },
+            PatchOperation::Move {
+                from: "ports[-1]".to_string(),
+                path: "last".to_string(),
+            },
+            PatchOperation::Test {
+                path:  "backup.port".to_string(),
+                value: Value::Integer(9090),
+            },
+        ])?;
+
+        assert_eq!(
+            value.get("ports"),
+            Some(&Value::Array(vec![
+                Value::Integer(0),
+                Value::Integer(1),
+                Value::Integer(2)
+            ]))
+        );
+        assert_eq!(value.get("server.host"), None);
+        assert_eq!(value.get("server.port"), Some(&Value::Integer(9090)));
+        assert_eq!(value.get("backup.port"), Some(&Value::Integer(9090)));
+        assert_eq!(value.get("last"), Some(&Value::Integer(3)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_patch_is_atomic() {
+        let mut value = config();
+        let result = value.apply_patch(&[
+            PatchOperation::Remove {
+                path: "server.port".to_string(),
+            },
+            PatchOperation::Test {
+                path:  "server.host".to_string(),
+                value: Value::String("example.com".to_string()),
+            },
+        ]);
+        assert!(result.is_err());
+        assert_eq!(value, config());
+
+        for operation in [
+            PatchOperation::Remove {
+                path: "missing".to_string(),
+            },
+            PatchOperation::Replace {
+                path:  "/server/missing".to_string(),
+                value: Value::Null,
+            },
+            PatchOperation::Add {
+                path:  "missing.child".to_string(),
+                value: Value::Null,
+            },
+            PatchOperation::Add {
+                path:  "ports[5]".to_string(),
+                value: Value::Null,
+            },
+            PatchOperation::Move {
+                from: "server".to_string(),
+                path: "server.inner".to_string(),
+            },
+        ] {
+            assert!(value.apply_patch(&[operation]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_json_pointer_rejects_negative_index() -> anyhow::Result<()> {
+        let mut value = config();
+        for path in ["/ports/-1", "/ports/01", "/ports/+1"] {
+            for operation in [
+                PatchOperation::Add {
+                    path:  path.to_string(),
+                    value: Value::Null,
+                },
+                PatchOperation::Remove {
+                    path: path.to_string(),
+                },
+                PatchOperation::Replace {
+                    path:  path.to_string(),
+                    value: Value::Null,
+                },
+            ] {
+                let err =
+                    value.apply_patch(&[operation]).expect_err("invalid index");
+                assert!(matches!(err, Error::PatchError { .. }), "{err}");
+            }
+        }
+        assert_eq!(value, config());
+
+        value.apply_patch(&[PatchOperation::Remove {
+            path: "ports[-1]".to_string(),
+        }])?;
+        assert_eq!(
+            value.get("ports"),
+            Some(&Value::Array(vec![Value::Integer(1)]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_patch_negative_subscripts() -> anyhow::Result<()> {
+        let mut value = config();
+        value.apply_patch(&[PatchOperation::Add {
+            path:  "ports[-1]".to_string(),
+            value: Value::Integer(0),
+        }])?;
+        assert_eq!(
+            value.get("ports"),
+            Some(&Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(0),
+                Value::Integer(2)
+            ]))
+        );
+        assert!(
+            value
+                .apply_patch(&[PatchOperation::Add {
+                    path:  "ports[-4]".to_string(),
+                    value: Value::Null,
+                }])
+                .is_err()
+        );
+
+        value.set("servers[+].host", Value::String("a".to_string()))?;
+        for (from, path) in [
+            ("servers[0]", "/servers/0/inner"),
+            ("/servers/0", "servers[-1].inner"),
+        ] {
+            let err = value
+                .apply_patch(&[PatchOperation::Move {
+                    from: from.to_string(),
+                    path: path.to_string(),
+                }])
+                .expect_err("move into itself");
+            assert!(
+                matches!(err, Error::PatchError {
+                    source: AccessError::MoveIntoChild(_),
+                    ..
+                }),
+                "{err}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_patch() -> anyhow::Result<()> {
+        let mut patch = Value::Table(Map::new());
+        patch.set("op", Value::String("replace".to_string()))?;
+        patch.set("path", Value::String("/server/port".to_string()))?;
+        patch.set("value", Value::Integer(1))?;
+        let operation: PatchOperation = patch.try_deserialize()?;
+        assert_eq!(operation, PatchOperation::Replace {
+            path:  "/server/port".to_string(),
+            value: Value::Integer(1),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,400 @@
+use std::str::FromStr;
+
+use super::{
+    expr::parse_quoted,
+    flatten::{
+        child_path,
+        index_path,
+    },
+    set::resolve_index,
+};
+use crate::{
+    Error,
+    Result,
+    Value,
+    errors::SyntaxError,
+};
+
+/// A key expression that can match several values, as used by
+/// [`Value::query`].
+///
+/// On top of the syntax `get` accepts, a query may use:
+///
+/// - `*` to match every entry of a table or element of an array, as in
+///   `plugins.*.enabled` or `servers[*].host`;
+/// - `..` to match at any depth, as in `..timeout` or `upstreams..port`;
+/// - `[start:end]` to match a slice of an array, as in `items[1:3]`. Either
+///   bound may be left out and negative bounds count from the end.
+///
+/// Keys are quoted as in `get`, so `hosts."*"` matches a key named `*`
+/// rather than every host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+/// One segment of a query, and whether it matches at any depth below the
+/// values selected so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Step {
+    descendant: bool,
+    selector:   Selector,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Selector {
+    Key(String),
+    Index(isize),
+    Slice(Option<isize>, Option<isize>),
+    Wildcard,
+}
+
+impl FromStr for Query {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let error = |source: SyntaxError| Error::ExprError {
+            expr: s.to_string(),
+            source,
+        };
+        let mut steps = Vec::new();
+        let mut descendant = false;
+        let mut rest = s;
+        while !rest.is_empty() {
+            if let Some(tail) = rest.strip_prefix("..") {
+                if descendant {
+                    return Err(error(SyntaxError::Unexpected('.')));
+                }
+                descendant = true;
+                rest = tail;
+                continue;
+            }
+            if let Some(tail) = rest.strip_prefix('.') {
+                if steps.is_empty() || descendant {
+                    return Err(error(SyntaxError::Unexpected('.')));
+                }
+                rest = tail;
+                continue;
+            }
+            let selector = if rest.starts_with('"') {
+                let (key, tail) = parse_quoted(rest).map_err(error)?;
+                if !tail.is_empty() && !tail.starts_with(['.', '[']) {
+                    return Err(error(SyntaxError::ExpectedSeparator));
+                }
+                rest = tail;
+                Selector::Key(key)
+            } else if rest.starts_with("[\"") {
+                let (key, tail) = parse_quoted(&rest[1..]).map_err(error)?;
+                rest = tail
+                    .strip_prefix(']')
+                    .ok_or_else(|| error(SyntaxError::Unmatched('[')))?;
+                Selector::Key(key)
+            } else if let Some(tail) = rest.strip_prefix('[') {
+                let (inner, tail) = tail
+                    .split_once(']')
+                    .ok_or_else(|| error(SyntaxError::Unmatched('[')))?;
+                rest = tail;
+                parse_bracket(inner.trim()).ok_or_else(|| {
+                    error(SyntaxError::InvalidSubscript(inner.to_string()))
+                })?
+            } else {
+                let end = rest.find(['.', '[', ']', '"']).unwrap_or(rest.len());
+                let (key, tail) = rest.split_at(end);
+                if key.is_empty() {
+                    return Err(error(SyntaxError::Unmatched(']')));
+                }
+                rest = tail;
+                if key == "*" {
+                    Selector::Wildcard
+                } else {
+                    Selector::Key(key.to_string())
+                }
+            };
+            steps.push(Step {
+                descendant,
+                selector,
+            });
+            descendant = false;
+        }
+        if descendant {
+            return Err(error(SyntaxError::ExpectedKey));
+        }
+        if steps.is_empty() {
+            return Err(error(SyntaxError::Empty));
+        }
+        Ok(Self { steps })
+    }
+}
+
+/// Parses the inside of a subscript: `*`, an index or a slice.
+fn parse_bracket(inner: &str) -> Option<Selector> {
+    if inner == "*" {
+        return Some(Selector::Wildcard);
+    }
+    let bound = |s: &str| {
+        let s = s.trim();
+        if s.is_empty() {
+            Some(None)
+        } else {
+            s.parse().ok().map(Some)
+        }
+    };
+    match inner.split_once(':') {
+        Some((start, end)) => Some(Selector::Slice(bound(start)?, bound(end)?)),
+        None => inner.parse().ok().map(Selector::Index),
+    }
+}
+
+impl Selector {
+    /// Pushes the children of `value` this selector matches onto `out`.
+    fn select<'a>(
+        &self,
+        path: &str,
+        value: &'a Value,
+        out: &mut Vec<(String, &'a Value)>,
+    ) {
+        match (self, value) {
+            (Self::Key(key), Value::Table(table)) => {
+                if let Some(child) = table.get(key) {
+                    out.push((child_path(path, key), child));
+                }
+            }
+            (Self::Key(key), Value::Array(array)) => {
+                if let Some((i, child)) = key
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| Some((i, array.get(i)?)))
+                {
+                    out.push((index_path(path, i), child));
+                }
+            }
+            (Self::Index(idx), Value::Array(array)) => {
+                if let Some(i) = resolve_index(*idx, array.len()) {
+                    out.push((index_path(path, i), &array[i]));
+                }
+            }
+            (Self::Slice(start, end), Value::Array(array)) => {
+                let len = array.len();
+                let bound = |b: Option<isize>, default: usize| match b {
+                    None => default,
+                    Some(b) if b < 0 => len.saturating_sub(b.unsigned_abs()),
+                    Some(b) => b.unsigned_abs().min(len),
+                };
+                let (start, end) = (bound(*start, 0), bound(*end, len));
+                for (i, child) in array.iter().enumerate().take(end).skip(start)
+                {
+                    out.push((index_path(path, i), child));
+                }
+            }
+            (Self::Wildcard, Value::Table(table)) => {
+                let mut entries: Vec<_> = table.iter().collect();
+                entries.sort_by_key(|(k, _)| *k);
+                for (key, child) in entries {
+                    out.push((child_path(path, key), child));
+                }
+            }
+            (Self::Wildcard, Value::Array(array)) => {
+                for (i, child) in array.iter().enumerate() {
+                    out.push((index_path(path, i), child));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Pushes `value` and everything below it onto `out`, parents first.
+fn push_descendants<'a>(
+    path: String,
+    value: &'a Value,
+    out: &mut Vec<(String, &'a Value)>,
+) {
+    let mut children = Vec::new();
+    Selector::Wildcard.select(&path, value, &mut children);
+    out.push((path, value));
+    for (path, child) in children {
+        push_descendants(path, child, out);
+    }
+}
+
+impl Value {
+    /// Returns the path and value of everything matching `query`.
+    ///
+    /// Besides plain keys, queries support wildcards (`servers[*].host`,
+    /// `plugins.*.enabled`), recursive descent (`..timeout`) and array
+    /// slices (`items[1:3]`); see [`Query`] for the syntax. Matches are
+    /// ordered parents before children, with table keys sorted, and their
+    /// paths use the same syntax as [`Value::flatten`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value
+    ///     .set("upstreams.api.timeout", Value::Integer(30))
+    ///     .expect("set timeout");
+    /// value
+    ///     .set("upstreams.auth.timeout", Value::Integer(5))
+    ///     .expect("set timeout");
+    ///
+    /// let timeouts = value.query("upstreams.*.timeout").expect("query");
+    /// assert_eq!(timeouts, vec![
+    ///     ("upstreams.api.timeout".to_string(), &Value::Integer(30)),
+    ///     ("upstreams.auth.timeout".to_string(), &Value::Integer(5)),
+    /// ]);
+    /// assert_eq!(value.query("..timeout").expect("query"), timeouts);
+    /// ```
+    pub fn query(&self, query: &str) -> Result<Vec<(String, &Self)>> {
+        let query: Query = query.parse()?;
+        Ok(self.query_with(&query))
+    }
+
+    /// Like [`Value::query`], for an already parsed query.
+    pub fn query_with(&self, query: &Query) -> Vec<(String, &Self)> {
+        let mut current = vec![(String::new(), self)];
+        for step in &query.steps {
+            let candidates = if step.descendant {
+                let mut all = Vec::new();
+                for (path, value) in current {
+                    push_descendants(path, value, &mut all);
+                }
+                all
+            } else {
+                current
+            };
+            let mut next = Vec::new();
+            for (path, value) in &candidates {
+                step.selector.select(path, value, &mut next);
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Map;
+
+    fn value() -> Result<Value> {
+        let mut value = Value::Table(Map::new());
+        let server = |host: &str, timeout: Option<i64>| {
+            let mut table = Map::new();
+            table.insert("host".to_string(), Value::String(host.to_string()));
+            if let Some(timeout) = timeout {
+                table.insert("timeout".to_string(), Value::Integer(timeout));
+            }
+            Value::Table(table)
+        };
+        value.set(
+            "servers",
+            Value::Array(vec![
+                server("a", Some(5)),
+                server("b", None),
+                server("c", Some(10)),
+            ]),
+        )?;
+        value.set("plugins.auth.enabled", Value::Boolean(true))?;
+        value.set("plugins.cache.enabled", Value::Boolean(false))?;
+        value.set("timeout", Value::Integer(1))?;
+        Ok(value)
+    }
+
+    fn paths(matches: Vec<(String, &Value)>) -> Vec<String> {
+        matches.into_iter().map(|(path, _)| path).collect()
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert!("servers[*].host".parse::<Query>().is_ok());
+        assert!("items[1:3]".parse::<Query>().is_ok());
+        assert!("items[:-1]".parse::<Query>().is_ok());
+        assert!("..timeout".parse::<Query>().is_ok());
+        assert!("".parse::<Query>().is_err());
+        assert!("a..".parse::<Query>().is_err());
+        assert!("a...b".parse::<Query>().is_err());
+        assert!("items[1".parse::<Query>().is_err());
+        assert!("items[x]".parse::<Query>().is_err());
+        assert!("items]".parse::<Query>().is_err());
+        assert!(r#"hosts."a.com"x"#.parse::<Query>().is_err());
+    }
+
+    #[test]
+    fn test_wildcard() -> anyhow::Result<()> {
+        let value = value()?;
+        assert_eq!(paths(value.query("servers[*].host")?), vec![
+            "servers[0].host",
+            "servers[1].host",
+            "servers[2].host",
+        ]);
+        assert_eq!(value.query("plugins.*.enabled")?, vec![
+            ("plugins.auth.enabled".to_string(), &Value::Boolean(true)),
+            ("plugins.cache.enabled".to_string(), &Value::Boolean(false)),
+        ]);
+        assert!(value.query("plugins.*.missing")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive() -> anyhow::Result<()> {
+        let value = value()?;
+        assert_eq!(paths(value.query("..timeout")?), vec![
+            "timeout",
+            "servers[0].timeout",
+            "servers[2].timeout",
+        ]);
+        assert_eq!(paths(value.query("servers..timeout")?), vec![
+            "servers[0].timeout",
+            "servers[2].timeout",
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_slice() -> anyhow::Result<()> {
+        let value = value()?;
+        assert_eq!(paths(value.query("servers[1:3].host")?), vec![
+            "servers[1].host",
+            "servers[2].host",
+        ]);
+        assert_eq!(paths(value.query("servers[-1:]")?), vec!["servers[2]"]);
+        assert_eq!(paths(value.query("servers[:1]")?), vec!["servers[0]"]);
+        assert!(value.query("servers[2:1]")?.is_empty());
+        assert_eq!(paths(value.query("servers[-1].host")?), vec![
+            "servers[2].host"
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quoted() -> anyhow::Result<()> {
+        let mut value = Value::Table(Map::new());
+        value.set(r#"hosts."example.com".port"#, Value::Integer(443))?;
+        value.set(r#"hosts."*".port"#, Value::Integer(80))?;
+        assert_eq!(paths(value.query("hosts.*.port")?), vec![
+            r#"hosts."*".port"#,
+            r#"hosts."example.com".port"#,
+        ]);
+        assert_eq!(paths(value.query(r#"hosts["*"].port"#)?), vec![
+            r#"hosts."*".port"#
+        ]);
+        for (path, _) in value.query("hosts.*.port")? {
+            assert_eq!(value.query(&path)?.len(), 1, "{path}");
+        }
+        value.set(r#"aliases["example.com"][+]"#, Value::Integer(1))?;
+        assert_eq!(paths(value.query(r#"aliases["example.com"][0]"#)?), vec![
+            r#"aliases."example.com"[0]"#
+        ]);
+        assert_eq!(
+            paths(value.query(r#"..".com".port"#)?),
+            Vec::<String>::new()
+        );
+        assert_eq!(paths(value.query(r#"hosts."example.com""#)?), vec![
+            r#"hosts."example.com""#,
+        ]);
+        Ok(())
+    }
+}
@@ -1,5 +1,12 @@
+use std::fmt::Display;
+
 use super::{
     expr::Expression,
+    flatten::{
+        child_path,
+        for_each_leaf,
+        index_path,
+    },
     key::Key,
 };
 use crate::{
@@ -7,110 +14,457 @@ use crate::{
     Map,
     Result,
     Value,
+    errors::AccessError,
 };
 
 impl Value {
+    /// Sets the value at `key`, creating missing tables along the way.
+    ///
+    /// Array elements are addressed with subscripts (`hosts[0]`, or
+    /// `hosts[-1]` for the last one) and must already exist: writing past the
+    /// end of an array is an error rather than wrapping around. Use
+    /// `hosts[+]` to append, or [`Value::push`] and [`Value::insert`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value
+    ///     .set("hosts[+]", Value::String("a".to_string()))
+    ///     .expect("append host");
+    /// value
+    ///     .set("hosts[+].name", Value::String("b".to_string()))
+    ///     .expect("append host");
+    /// value
+    ///     .set("hosts[0]", Value::String("c".to_string()))
+    ///     .expect("replace host");
+    ///
+    /// assert_eq!(value.get("hosts[0]"), Some(&Value::String("c".to_string())));
+    /// assert_eq!(
+    ///     value.get("hosts[1].name"),
+    ///     Some(&Value::String("b".to_string()))
+    /// );
+    /// assert!(value.set("hosts[2]", Value::Null).is_err());
+    /// ```
     #[allow(clippy::needless_pass_by_value)]
     pub fn set<K: Key>(&mut self, key: K, value: Self) -> Result<&mut Self> {
         let expr = key.to_key()?;
+        let (last, parents) = segments(&expr)
+            .split_last()
+            .expect("expressions have at least one segment");
+        let mut current = self;
+        for segment in parents {
+            current = current.step_mut(segment)?;
+        }
+        current.set_segment(last, value)?;
+        Ok(current)
+    }
 
-        match expr {
+    /// Appends `value` to the array at `key`, creating the array if the key
+    /// is missing or null.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value.push("ports", Value::Integer(80)).expect("push port");
+    /// value.push("ports", Value::Integer(443)).expect("push port");
+    ///
+    /// assert_eq!(
+    ///     value.get("ports"),
+    ///     Some(&Value::Array(vec![Value::Integer(80), Value::Integer(443)]))
+    /// );
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn push<K: Key>(&mut self, key: K, value: Self) -> Result<()> {
+        self.array_mut(&key.to_key()?)?.push(value);
+        Ok(())
+    }
+
+    /// Inserts `value` into the array at `key` before `index`, shifting the
+    /// following elements to the right. `index` may be the length of the
+    /// array, which appends.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value.push("ports", Value::Integer(443)).expect("push port");
+    /// value
+    ///     .insert("ports", 0, Value::Integer(80))
+    ///     .expect("insert port");
+    ///
+    /// assert_eq!(value.get("ports[0]"), Some(&Value::Integer(80)));
+    /// assert!(value.insert("ports", 3, Value::Integer(8080)).is_err());
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn insert<K: Key>(
+        &mut self,
+        key: K,
+        index: usize,
+        value: Self,
+    ) -> Result<()> {
+        let expr = key.to_key()?;
+        let array = self.array_mut(&expr)?;
+        if index > array.len() {
+            let index = isize::try_from(index).unwrap_or(isize::MAX);
+            return Err(out_of_bounds(index, &expr, array.len()));
+        }
+        array.insert(index, value);
+        Ok(())
+    }
+
+    /// Returns the array at `expr`, turning a missing or null value into an
+    /// empty array.
+    fn array_mut(&mut self, expr: &Expression) -> Result<&mut Vec<Self>> {
+        let mut current = self;
+        for segment in segments(expr) {
+            current = current.step_mut(segment)?;
+        }
+        current.make_array(expr)
+    }
+
+    /// Returns the array `self` holds, turning null into an empty array.
+    fn make_array(&mut self, path: impl Display) -> Result<&mut Vec<Self>> {
+        if matches!(self, Self::Null) {
+            *self = Self::Array(Vec::new());
+        }
+        match self {
+            Self::Array(array) => Ok(array),
+            other => Err(set_error(path, type_mismatch("an array", other))),
+        }
+    }
+
+    /// Moves one segment down from `self` while setting, creating missing
+    /// table entries as nulls and replacing scalars with tables.
+    fn step_mut(&mut self, segment: &Expression) -> Result<&mut Self> {
+        match segment {
             Expression::Identifier(id) => {
-                match self {
-                    Self::Table(table) => {
-                        table.insert(id, value);
-                    }
-                    Self::Array(arr) => {
-                        let idx = id
-                            .parse::<isize>()
-                            .map_err(|e| Error::SetValueError(e.to_string()))?;
-                        let idx = idx.rem_euclid(arr.len() as isize) as usize;
-                        // if idx >= arr.len() {
-                        //     return Err(Error::SetValueError(format!("Index {}
-                        // out of bounds for array of length {}", idx,
-                        // arr.len()))); }
-                        arr.resize(idx.max(arr.len()), Self::Null);
-                        arr[idx] = value;
-                    }
-                    _ => {
-                        *self =
-                            Self::Table([(id, value)].into_iter().collect());
-                    }
+                if let Self::Array(array) = self {
+                    return element_mut(array, parse_index(id)?, id);
                 }
-                Ok(self)
+                if !matches!(self, Self::Table(_)) {
+                    *self = Self::Table(Map::new());
+                }
+                let table = self.table_mut(id)?;
+                Ok(table.entry(id.clone()).or_insert(Self::Null))
             }
             Expression::Subscript(id, idx) => {
-                match self {
-                    Self::Table(table) => {
-                        let arr = if let Some(existing) = table.get_mut(&id) {
-                            existing
-                        } else {
-                            table.insert(id.clone(), Self::Array(Vec::new()));
-                            table.get_mut(&id).expect("Failed to get mut table")
-                        };
-
-                        if let Self::Array(arr) = arr {
-                            let idx =
-                                idx.rem_euclid(arr.len() as isize) as usize;
-                            // if idx >= arr.len() {
-                            //     return
-                            // Err(Error::SetValueError(format!("Index {} out of
-                            // bounds for array of length {}", idx,
-                            // arr.len()))); }
-                            arr.resize(idx.max(arr.len()), Self::Null);
-                            arr[idx] = value;
-                            Ok(self)
-                        } else {
-                            *arr = Self::Array(vec![value]);
-                            Ok(self)
-                        }
+                match self.table_mut(id)?.get_mut(id) {
+                    Some(Self::Array(array)) => element_mut(array, *idx, id),
+                    Some(other) => {
+                        Err(set_error(id, type_mismatch("an array", other)))
                     }
-                    _ => Err(Error::SetValueError(format!(
-                        "Expected a table, got {}",
-                        self.value_type()
-                    ))),
+                    None => Err(out_of_bounds(*idx, id, 0)),
                 }
             }
+            Expression::Push(id) => {
+                let entry =
+                    self.table_mut(id)?.entry(id.clone()).or_insert(Self::Null);
+                let array = entry.make_array(id)?;
+                array.push(Self::Null);
+                Ok(array.last_mut().expect("just pushed"))
+            }
             Expression::Child(exprs) => {
                 let mut current = self;
-                for (i, e) in exprs.iter().enumerate() {
-                    if i == exprs.len() - 1 {
-                        return current.set(e.clone(), value);
-                    }
-                    current = if let Self::Table(table) = current {
-                        if !table.contains_key(&e.to_string()) {
-                            table
-                                .insert(e.to_string(), Self::Table(Map::new()));
-                        }
-                        table
-                            .get_mut(&e.to_string())
-                            .expect("Failed to get mut table")
-                    } else {
-                        let mut new_table = Map::new();
-                        new_table
-                            .insert(e.to_string(), Self::Table(Map::new()));
-                        *current = Self::Table(new_table);
-                        current.get_mut(e).expect("Failed to get mut table")
-                    };
+                for e in exprs {
+                    current = current.step_mut(e)?;
                 }
                 Ok(current)
             }
         }
     }
 
+    fn set_segment(&mut self, segment: &Expression, value: Self) -> Result<()> {
+        match (segment, &mut *self) {
+            (Expression::Identifier(id), Self::Table(table)) => {
+                table.insert(id.clone(), value);
+            }
+            (Expression::Identifier(id), Self::Array(array)) => {
+                *element_mut(array, parse_index(id)?, id)? = value;
+            }
+            (Expression::Identifier(id), _) => {
+                *self =
+                    Self::Table([(id.clone(), value)].into_iter().collect());
+            }
+            _ => *self.step_mut(segment)? = value,
+        }
+        Ok(())
+    }
+
+    /// Returns the table `self` holds to set `key` in, turning null into an
+    /// empty table.
+    fn table_mut(&mut self, key: &str) -> Result<&mut Map<String, Self>> {
+        if matches!(self, Self::Null) {
+            *self = Self::Table(Map::new());
+        }
+        match self {
+            Self::Table(table) => Ok(table),
+            other => Err(set_error(key, type_mismatch("a table", other))),
+        }
+    }
+
+    /// Returns the path of the first array `key` goes through in `self`,
+    /// e.g. `servers` for `servers[0].host`.
+    pub(crate) fn array_root(&self, key: &str) -> Option<String> {
+        let expr: Expression = key.parse().ok()?;
+        let mut prefix = Vec::new();
+        let mut current = self;
+        for segment in segments(&expr) {
+            let id = match segment {
+                Expression::Identifier(id) => id,
+                Expression::Subscript(id, _) | Expression::Push(id) => {
+                    prefix.push(Expression::Identifier(id.clone()));
+                    return Some(Expression::Child(prefix).to_string());
+                }
+                Expression::Child(_) => return None,
+            };
+            match current {
+                Self::Array(_) if !prefix.is_empty() => {
+                    return Some(Expression::Child(prefix).to_string());
+                }
+                Self::Table(table) => current = table.get(id)?,
+                _ => return None,
+            }
+            prefix.push(Expression::Identifier(id.clone()));
+        }
+        None
+    }
+
+    /// Removes the value at `key` and returns it, or `None` if there is
+    /// nothing to remove.
+    ///
+    /// Table entries are removed by key and array elements by index, shifting
+    /// the following elements to the left. Negative subscripts count from the
+    /// end of the array.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::Value;
+    ///
+    /// let mut value = Value::Null;
+    /// value
+    ///     .set("server.port", Value::Integer(8080))
+    ///     .expect("set port");
+    ///
+    /// assert_eq!(value.remove("server.port"), Some(Value::Integer(8080)));
+    /// assert_eq!(value.get("server.port"), None);
+    /// assert_eq!(value.remove("server.port"), None);
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn remove<K: Key>(&mut self, key: K) -> Option<Self> {
+        let expr = key.to_key().ok()?;
+        self.remove_internal(&expr)
+    }
+
+    fn remove_internal(&mut self, expr: &Expression) -> Option<Self> {
+        match expr {
+            Expression::Identifier(id) => match self {
+                Self::Table(table) => table.remove(id),
+                Self::Array(arr) => {
+                    let idx = id.parse::<usize>().ok()?;
+                    (idx < arr.len()).then(|| arr.remove(idx))
+                }
+                _ => None,
+            },
+            Expression::Subscript(id, idx) => match self {
+                Self::Table(table) => match table.get_mut(id)? {
+                    Self::Array(arr) => {
+                        let idx = resolve_index(*idx, arr.len())?;
+                        Some(arr.remove(idx))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            Expression::Push(_) => None,
+            Expression::Child(exprs) => {
+                let (last, parents) = exprs.split_last()?;
+                let mut current = self;
+                for e in parents {
+                    current = current.existing_mut(e)?;
+                }
+                current.remove_internal(last)
+            }
+        }
+    }
+
+    /// Like `get_mut`, but never inserts missing entries.
+    fn existing_mut(&mut self, expr: &Expression) -> Option<&mut Self> {
+        match expr {
+            Expression::Identifier(id) => match self {
+                Self::Table(table) => table.get_mut(id),
+                Self::Array(arr) => arr.get_mut(id.parse::<usize>().ok()?),
+                _ => None,
+            },
+            Expression::Subscript(id, idx) => match self {
+                Self::Table(table) => match table.get_mut(id)? {
+                    Self::Array(arr) => {
+                        let idx = resolve_index(*idx, arr.len())?;
+                        arr.get_mut(idx)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            Expression::Push(_) => None,
+            Expression::Child(exprs) => {
+                let mut current = self;
+                for e in exprs {
+                    current = current.existing_mut(e)?;
+                }
+                Some(current)
+            }
+        }
+    }
+
     pub fn merge(&mut self, other: &Self) {
+        self.merge_with(other, &MergeOptions::default());
+    }
+
+    /// Merges `other` into this value, combining arrays as `options` says.
+    ///
+    /// Tables are merged recursively and any other value in `other` replaces
+    /// the one in `self`, exactly like [`Value::merge`]. Arrays found on both
+    /// sides are combined with the [`MergeStrategy`] configured for their
+    /// path.
+    ///
+    /// Returns the type conflicts met on the way, i.e. every path where a
+    /// table or an array was replaced by a value of another type, or the
+    /// other way round.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::{
+    ///     MergeOptions,
+    ///     MergeStrategy,
+    ///     Value,
+    /// };
+    ///
+    /// let ips = |ip: &str| Value::Array(vec![Value::String(ip.to_string())]);
+    /// let mut base = Value::Null;
+    /// base.set("allowed_ips", ips("10.0.0.1")).expect("set ips");
+    /// let mut layer = Value::Null;
+    /// layer.set("allowed_ips", ips("10.0.0.2")).expect("set ips");
+    ///
+    /// let options =
+    ///     MergeOptions::new().path("allowed_ips", MergeStrategy::Append);
+    /// base.merge_with(&layer, &options);
+    ///
+    /// assert_eq!(
+    ///     base.get("allowed_ips[1]"),
+    ///     Some(&Value::String("10.0.0.2".to_string()))
+    /// );
+    /// ```
+    pub fn merge_with(
+        &mut self,
+        other: &Self,
+        options: &MergeOptions,
+    ) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+        self.merge_at("", other, options, &mut conflicts);
+        conflicts
+    }
+
+    fn merge_at(
+        &mut self,
+        path: &str,
+        other: &Self,
+        options: &MergeOptions,
+        conflicts: &mut Vec<MergeConflict>,
+    ) {
         match (self, other) {
             (Self::Table(a), Self::Table(b)) => {
                 for (k, v) in b {
-                    a.entry(k.clone())
-                        .and_modify(|a| a.merge(v))
-                        .or_insert(v.clone());
+                    if options.deletes(v) {
+                        a.remove(k);
+                    } else if let Some(existing) = a.get_mut(k) {
+                        existing.merge_at(
+                            &child_path(path, k),
+                            v,
+                            options,
+                            conflicts,
+                        );
+                    } else {
+                        a.insert(k.clone(), v.without_deletes(options));
+                    }
+                }
+            }
+            (Self::Array(a), Self::Array(b)) => {
+                merge_arrays(path, a, b, options, conflicts);
+            }
+            (this, other) => {
+                if MergeConflict::is_conflict(this, other) {
+                    conflicts.push(MergeConflict {
+                        path:     path.to_string(),
+                        existing: this.value_type(),
+                        incoming: other.value_type(),
+                    });
                 }
+                *this = other.without_deletes(options);
             }
-            (this, other) => *this = other.clone(),
         }
     }
 
+    /// Copies this value, dropping the table entries that `options` treats as
+    /// deletions, since there is nothing for them to delete.
+    fn without_deletes(&self, options: &MergeOptions) -> Self {
+        match self {
+            Self::Table(table) if options.deletes_anything() => Self::Table(
+                table
+                    .iter()
+                    .filter(|(_, v)| !options.deletes(v))
+                    .map(|(k, v)| (k.clone(), v.without_deletes(options)))
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// Compares this value with `other` and returns the changes that turn
+    /// `self` into `other`.
+    ///
+    /// Tables and arrays are compared recursively, so every change is reported
+    /// at a leaf path such as `server.port` or `servers[1].host`. A subtree
+    /// that only exists on one side, or that replaces a value of another type,
+    /// is reported leaf by leaf. Changes are
+    /// sorted by key, which keeps the result stable across runs.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use realme::{
+    ///     Change,
+    ///     Value,
+    /// };
+    ///
+    /// let mut old = Value::Null;
+    /// old.set("server.port", Value::Integer(8080))
+    ///     .expect("set port");
+    /// let mut new = old.clone();
+    /// new.set("server.port", Value::Integer(9090))
+    ///     .expect("set port");
+    ///
+    /// assert_eq!(old.diff(&new), vec![Change::Modified {
+    ///     path: "server.port".to_string(),
+    ///     old:  Value::Integer(8080),
+    ///     new:  Value::Integer(9090),
+    /// }]);
+    /// ```
+    pub fn diff(&self, other: &Self) -> Vec<Change> {
+        let mut changes = Vec::new();
+        diff_into(String::new(), self, other, &mut changes);
+        changes
+    }
+
     // pub fn with<K: Key + Clone, F>(&mut self, key: K, f: F) -> &mut Self
     // where
     //     F: FnOnce(&mut Self),
@@ -123,3 +477,699 @@ impl Value {
     //     self
     // }
 }
+
+/// How two arrays are combined when one layer is merged on top of another.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The later array replaces the earlier one.
+    #[default]
+    Replace,
+    /// The later elements are appended to the earlier ones.
+    Append,
+    /// The later elements are inserted before the earlier ones.
+    Prepend,
+    /// The later elements are appended unless an equal element is already
+    /// present.
+    Union,
+    /// Elements at the same index are merged, extra elements are appended.
+    ByIndex,
+    /// Tables with the same value in the given field are merged, other
+    /// elements are appended. E.g. `ByKey("name".into())` merges `servers`
+    /// entries by their `name`.
+    ByKey(String),
+}
+
+/// Selects the [`MergeStrategy`] used for arrays during a merge.
+///
+/// Strategies are configured per key path, such as `servers` or
+/// `network.allowed_ips`. Array indices are not part of the path, so a
+/// strategy for `servers.tags` applies to the `tags` of every server. Arrays
+/// without a strategy of their own use the default one.
+///
+/// By default a `Null` in a later layer overwrites the earlier value. With
+/// [`MergeOptions::null_deletes`] or [`MergeOptions::tombstone`], a later
+/// layer can instead remove a key it inherited.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    strategy:     MergeStrategy,
+    paths:        Map<String, MergeStrategy>,
+    null_deletes: bool,
+    tombstone:    Option<String>,
+}
+
+impl MergeOptions {
+    /// Creates options that replace every array, like [`Value::merge`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the strategy for arrays without a path-specific strategy.
+    #[must_use]
+    pub fn strategy(mut self, strategy: MergeStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the strategy for the array at `path`.
+    #[must_use]
+    pub fn path(
+        mut self,
+        path: impl Into<String>,
+        strategy: MergeStrategy,
+    ) -> Self {
+        self.paths.insert(path.into(), strategy);
+        self
+    }
+
+    /// Makes an explicit `Null` in a later layer delete the key instead of
+    /// overwriting it.
+    #[must_use]
+    pub const fn null_deletes(mut self, enabled: bool) -> Self {
+        self.null_deletes = enabled;
+        self
+    }
+
+    /// Makes a string equal to `marker` in a later layer delete the key. This
+    /// works for formats without a null, e.g. `key = "~delete"` in TOML.
+    #[must_use]
+    pub fn tombstone(mut self, marker: impl Into<String>) -> Self {
+        self.tombstone = Some(marker.into());
+        self
+    }
+
+    fn deletes(&self, value: &Value) -> bool {
+        match value {
+            Value::Null => self.null_deletes,
+            Value::String(s) => self.tombstone.as_ref() == Some(s),
+            _ => false,
+        }
+    }
+
+    const fn deletes_anything(&self) -> bool {
+        self.null_deletes || self.tombstone.is_some()
+    }
+
+    fn strategy_for(&self, path: &str) -> &MergeStrategy {
+        self.paths.get(path).unwrap_or(&self.strategy)
+    }
+}
+
+fn merge_arrays(
+    path: &str,
+    a: &mut Vec<Value>,
+    b: &[Value],
+    options: &MergeOptions,
+    conflicts: &mut Vec<MergeConflict>,
+) {
+    match options.strategy_for(path) {
+        MergeStrategy::Replace => *a = b.to_vec(),
+        MergeStrategy::Append => a.extend_from_slice(b),
+        MergeStrategy::Prepend => {
+            a.splice(0..0, b.iter().cloned());
+        }
+        MergeStrategy::Union => {
+            for v in b {
+                if !a.contains(v) {
+                    a.push(v.clone());
+                }
+            }
+        }
+        MergeStrategy::ByIndex => {
+            for (i, v) in b.iter().enumerate() {
+                if let Some(existing) = a.get_mut(i) {
+                    existing.merge_at(path, v, options, conflicts);
+                } else {
+                    a.push(v.clone());
+                }
+            }
+        }
+        MergeStrategy::ByKey(key) => {
+            let field = |v: &Value| match v {
+                Value::Table(table) => table.get(key).cloned(),
+                _ => None,
+            };
+            for v in b {
+                let existing = field(v).and_then(|id| {
+                    a.iter_mut().find(|e| field(e).as_ref() == Some(&id))
+                });
+                if let Some(existing) = existing {
+                    existing.merge_at(path, v, options, conflicts);
+                } else {
+                    a.push(v.clone());
+                }
+            }
+        }
+    }
+}
+
+/// A table or an array replaced by a value of another type during a merge,
+/// as returned by [`Value::merge_with`].
+///
+/// These are usually layering mistakes, e.g. an override setting
+/// `database = "postgres://..."` over a `[database]` table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    /// The path where the conflict happened, e.g. `database`.
+    pub path:     String,
+    /// The type of the value that was there before, e.g. `table`.
+    pub existing: &'static str,
+    /// The type of the value that replaced it, e.g. `string`.
+    pub incoming: &'static str,
+}
+
+impl MergeConflict {
+    /// Whether replacing `existing` with `incoming` changes the shape of the
+    /// configuration. Nulls never conflict, and neither do two scalars.
+    fn is_conflict(existing: &Value, incoming: &Value) -> bool {
+        let shape = |v: &Value| match v {
+            Value::Null => None,
+            Value::Table(_) => Some(0),
+            Value::Array(_) => Some(1),
+            _ => Some(2),
+        };
+        matches!(
+            (shape(existing), shape(incoming)),
+            (Some(a), Some(b)) if a != b
+        )
+    }
+}
+
+impl std::fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "<root>"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{} at `{path}` was replaced by {}",
+            self.existing, self.incoming
+        )
+    }
+}
+
+/// A single difference between two values, as returned by [`Value::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// The path only exists in the new value.
+    Added { path: String, value: Value },
+    /// The path only exists in the old value.
+    Removed { path: String, value: Value },
+    /// The path exists in both values with different contents.
+    Modified {
+        path: String,
+        old:  Value,
+        new:  Value,
+    },
+}
+
+impl Change {
+    /// Returns the path of the changed leaf, e.g. `server.port`.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Added { path, .. } |
+            Self::Removed { path, .. } |
+            Self::Modified { path, .. } => path,
+        }
+    }
+}
+
+/// Resolves a possibly negative subscript against an array of length `len`.
+pub(super) fn resolve_index(idx: isize, len: usize) -> Option<usize> {
+    let idx = if idx < 0 {
+        len.checked_sub(idx.unsigned_abs())?
+    } else {
+        idx.cast_unsigned()
+    };
+    (idx < len).then_some(idx)
+}
+
+/// Returns the segments of `expr`, e.g. `a`, `b[0]` and `c` for `a.b[0].c`.
+fn segments(expr: &Expression) -> &[Expression] {
+    match expr {
+        Expression::Child(exprs) => exprs,
+        other => std::slice::from_ref(other),
+    }
+}
+
+fn parse_index(id: &str) -> Result<isize> {
+    id.parse()
+        .map_err(|_e| set_error(id, AccessError::InvalidIndex(id.to_string())))
+}
+
+/// Returns the element at `idx` of the array at `id`, failing if it is out of
+/// bounds. Negative indexes count from the end.
+fn element_mut<'a>(
+    array: &'a mut [Value],
+    idx: isize,
+    id: &str,
+) -> Result<&'a mut Value> {
+    let len = array.len();
+    resolve_index(idx, len)
+        .map(|i| &mut array[i])
+        .ok_or_else(|| out_of_bounds(idx, id, len))
+}
+
+fn out_of_bounds(index: isize, path: impl Display, len: usize) -> Error {
+    set_error(path, AccessError::OutOfBounds { index, len })
+}
+
+pub(super) const fn type_mismatch(
+    expected: &'static str,
+    found: &Value,
+) -> AccessError {
+    AccessError::TypeMismatch {
+        expected,
+        found: found.value_type(),
+    }
+}
+
+fn set_error(key: impl Display, source: AccessError) -> Error {
+    Error::SetValueError {
+        key: key.to_string(),
+        source,
+    }
+}
+
+fn diff_into(
+    path: String,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Value::Table(a), Value::Table(b)) => {
+            let mut keys: Vec<&String> = a
+                .iter()
+                .chain(b.iter().filter(|(k, _)| !a.contains_key(*k)))
+                .map(|(k, _)| k)
+                .collect();
+            keys.sort();
+            for key in keys {
+                diff_entry(
+                    child_path(&path, key),
+                    a.get(key),
+                    b.get(key),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_entry(index_path(&path, i), a.get(i), b.get(i), changes);
+            }
+        }
+        // A table or array replaced by a value of another type: its leaves
+        // are gone and those of the new value appear.
+        (Value::Table(_) | Value::Array(_), _) |
+        (_, Value::Table(_) | Value::Array(_)) => {
+            diff_entry(path.clone(), Some(old), None, changes);
+            diff_entry(path, None, Some(new), changes);
+        }
+        _ if old != new => changes.push(Change::Modified {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Diffs an entry that may be missing on either side.
+fn diff_entry(
+    path: String,
+    old: Option<&Value>,
+    new: Option<&Value>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Some(old), Some(new)) => diff_into(path, old, new, changes),
+        (Some(old), None) => for_each_leaf(path, old, &mut |path, leaf| {
+            changes.push(Change::Removed {
+                path,
+                value: leaf.clone(),
+            });
+        }),
+        (None, Some(new)) => for_each_leaf(path, new, &mut |path, leaf| {
+            changes.push(Change::Added {
+                path,
+                value: leaf.clone(),
+            });
+        }),
+        (None, None) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(&str, Value)]) -> Value {
+        let mut value = Value::Table(Map::new());
+        for (key, v) in entries {
+            value.set(*key, v.clone()).expect("set value");
+        }
+        value
+    }
+
+    #[test]
+    fn test_set_array_elements() -> anyhow::Result<()> {
+        let mut value = Value::Null;
+        assert!(value.set("list[0]", Value::Integer(1)).is_err());
+        value.set("list[+]", Value::Integer(1))?;
+        value.set("list[+]", Value::Integer(2))?;
+        value.set("list[-1]", Value::Integer(3))?;
+        assert_eq!(value.get("list"), Some(&ints(&[1, 3])));
+        assert!(value.set("list[2]", Value::Integer(4)).is_err());
+        assert!(value.set("list[-3]", Value::Integer(4)).is_err());
+        assert!(value.set("list.x", Value::Integer(4)).is_err());
+        assert_eq!(value.get("list"), Some(&ints(&[1, 3])));
+
+        value.set("servers[+].name", Value::String("a".to_string()))?;
+        value.set("servers[0].port", Value::Integer(80))?;
+        assert_eq!(value.get("servers[0].port"), Some(&Value::Integer(80)));
+        assert!(value.set("servers[1].port", Value::Integer(80)).is_err());
+
+        value.set("name", Value::String("app".to_string()))?;
+        assert!(value.set("name[+]", Value::Null).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_and_insert() -> anyhow::Result<()> {
+        let mut value = Value::Null;
+        value.push("a.list", Value::Integer(2))?;
+        value.insert("a.list", 0, Value::Integer(1))?;
+        value.insert("a.list", 2, Value::Integer(4))?;
+        value.insert("a.list", 2, Value::Integer(3))?;
+        assert_eq!(value.get("a.list"), Some(&ints(&[1, 2, 3, 4])));
+        assert!(value.insert("a.list", 5, Value::Integer(5)).is_err());
+        assert_eq!(value.remove("a.list[1]"), Some(Value::Integer(2)));
+        assert_eq!(value.get("a.list"), Some(&ints(&[1, 3, 4])));
+        assert!(value.push("a", Value::Integer(1)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut value = table(&[
+            ("server.host", Value::String("localhost".to_string())),
+            ("server.port", Value::Integer(8080)),
+            (
+                "ports",
+                Value::Array(vec![
+                    Value::Integer(1),
+                    Value::Integer(2),
+                    Value::Integer(3),
+                ]),
+            ),
+        ]);
+
+        assert_eq!(value.remove("server.port"), Some(Value::Integer(8080)));
+        assert_eq!(value.get("server.port"), None);
+        assert!(value.get("server.host").is_some());
+        assert_eq!(value.remove("ports[-1]"), Some(Value::Integer(3)));
+        assert_eq!(value.remove("ports[0]"), Some(Value::Integer(1)));
+        assert_eq!(
+            value.get("ports"),
+            Some(&Value::Array(vec![Value::Integer(2)]))
+        );
+        assert_eq!(
+            value.remove("server"),
+            Some(table(&[("host", Value::String("localhost".to_string()))]))
+        );
+
+        assert_eq!(value.remove("server.host"), None);
+        assert_eq!(value.remove("ports[3]"), None);
+        assert_eq!(value.remove("missing.child"), None);
+        assert_eq!(value.get("missing"), None);
+    }
+
+    fn ints(items: &[i64]) -> Value {
+        Value::Array(items.iter().copied().map(Value::Integer).collect())
+    }
+
+    fn strings(items: &[&str]) -> Value {
+        Value::Array(
+            items
+                .iter()
+                .map(|s| Value::String((*s).to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_merge_array_strategies() {
+        let base = table(&[("hosts", strings(&["a", "b"]))]);
+        let layer = table(&[("hosts", strings(&["b", "c"]))]);
+        let merged = |strategy: MergeStrategy| {
+            let mut value = base.clone();
+            value.merge_with(&layer, &MergeOptions::new().strategy(strategy));
+            value.get("hosts").cloned()
+        };
+
+        assert_eq!(merged(MergeStrategy::Replace), Some(strings(&["b", "c"])));
+        assert_eq!(
+            merged(MergeStrategy::Append),
+            Some(strings(&["a", "b", "b", "c"]))
+        );
+        assert_eq!(
+            merged(MergeStrategy::Prepend),
+            Some(strings(&["b", "c", "a", "b"]))
+        );
+        assert_eq!(
+            merged(MergeStrategy::Union),
+            Some(strings(&["a", "b", "c"]))
+        );
+        assert_eq!(merged(MergeStrategy::ByIndex), Some(strings(&["b", "c"])));
+
+        let mut value = base.clone();
+        value.merge(&layer);
+        assert_eq!(value.get("hosts"), Some(&strings(&["b", "c"])));
+    }
+
+    #[test]
+    fn test_merge_by_key_and_path() {
+        let server = |name: &str, port: i64| {
+            table(&[
+                ("name", Value::String(name.to_string())),
+                ("port", Value::Integer(port)),
+                ("tags", strings(&[name])),
+            ])
+        };
+        let mut value = table(&[
+            (
+                "servers",
+                Value::Array(vec![server("a", 1), server("b", 2)]),
+            ),
+            ("hosts", strings(&["x"])),
+        ]);
+        let mut updated_b = server("b", 20);
+        updated_b.set("tags", strings(&["new"])).expect("set tags");
+        let layer = table(&[
+            ("servers", Value::Array(vec![updated_b, server("c", 3)])),
+            ("hosts", strings(&["y"])),
+        ]);
+
+        let options = MergeOptions::new()
+            .path("servers", MergeStrategy::ByKey("name".to_string()))
+            .path("servers.tags", MergeStrategy::Append);
+        value.merge_with(&layer, &options);
+
+        let mut expected_b = server("b", 20);
+        expected_b
+            .set("tags", strings(&["b", "new"]))
+            .expect("set tags");
+        assert_eq!(
+            value.get("servers"),
+            Some(&Value::Array(vec![
+                server("a", 1),
+                expected_b,
+                server("c", 3)
+            ]))
+        );
+        assert_eq!(value.get("hosts"), Some(&strings(&["y"])));
+    }
+
+    #[test]
+    fn test_merge_deletes() {
+        let base = table(&[
+            ("db.host", Value::String("localhost".to_string())),
+            ("db.port", Value::Integer(5432)),
+            ("debug", Value::Boolean(true)),
+        ]);
+        let layer = table(&[
+            ("db.host", Value::Null),
+            ("db.port", Value::String("~delete".to_string())),
+            ("debug", Value::Null),
+            ("cache.ttl", Value::String("~delete".to_string())),
+            ("cache.size", Value::Null),
+        ]);
+
+        let mut value = base.clone();
+        value.merge(&layer);
+        assert_eq!(value.get("db.host"), Some(&Value::Null));
+        assert_eq!(value.get("cache.size"), Some(&Value::Null));
+
+        let mut value = base.clone();
+        value.merge_with(&layer, &MergeOptions::new().null_deletes(true));
+        assert_eq!(value.get("db.host"), None);
+        assert_eq!(
+            value.get("db.port"),
+            Some(&Value::String("~delete".to_string()))
+        );
+        assert_eq!(value.get("debug"), None);
+        assert_eq!(value.get("cache.size"), None);
+
+        let mut value = base;
+        value.merge_with(
+            &layer,
+            &MergeOptions::new().null_deletes(true).tombstone("~delete"),
+        );
+        assert_eq!(value.get("db"), Some(&Value::Table(Map::new())));
+        assert_eq!(value.get("cache"), Some(&Value::Table(Map::new())));
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let mut value = table(&[
+            ("database.host", Value::String("localhost".to_string())),
+            ("ports", Value::Array(vec![Value::Integer(1)])),
+            ("name", Value::String("app".to_string())),
+            ("debug", Value::Null),
+        ]);
+        let layer = table(&[
+            ("database", Value::String("postgres://".to_string())),
+            ("ports.http", Value::Integer(80)),
+            ("name", Value::Integer(1)),
+            ("debug", Value::Table(Map::new())),
+        ]);
+
+        let mut conflicts = value.merge_with(&layer, &MergeOptions::new());
+        conflicts.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(conflicts, vec![
+            MergeConflict {
+                path:     "database".to_string(),
+                existing: "table",
+                incoming: "string",
+            },
+            MergeConflict {
+                path:     "ports".to_string(),
+                existing: "array",
+                incoming: "table",
+            },
+        ]);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "table at `database` was replaced by string"
+        );
+        assert_eq!(
+            value.get("database"),
+            Some(&Value::String("postgres://".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let value = table(&[("a.b", Value::Integer(1))]);
+        assert!(value.diff(&value.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        let old = table(&[
+            ("server.host", Value::String("localhost".to_string())),
+            ("server.port", Value::Integer(8080)),
+            ("debug", Value::Boolean(true)),
+        ]);
+        let new = table(&[
+            ("server.host", Value::String("localhost".to_string())),
+            ("server.port", Value::Integer(9090)),
+            ("db.url", Value::String("pg://".to_string())),
+            ("db.pool", Value::Integer(4)),
+        ]);
+
+        assert_eq!(old.diff(&new), vec![
+            Change::Added {
+                path:  "db.pool".to_string(),
+                value: Value::Integer(4),
+            },
+            Change::Added {
+                path:  "db.url".to_string(),
+                value: Value::String("pg://".to_string()),
+            },
+            Change::Removed {
+                path:  "debug".to_string(),
+                value: Value::Boolean(true),
+            },
+            Change::Modified {
+                path: "server.port".to_string(),
+                old:  Value::Integer(8080),
+                new:  Value::Integer(9090),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_diff_arrays_and_types() {
+        let old = table(&[(
+            "ports",
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+        )]);
+        let new = table(&[
+            ("ports", Value::Array(vec![Value::Integer(1)])),
+            ("ports_extra", Value::Table(Map::new())),
+        ]);
+        let changes = old.diff(&new);
+        assert_eq!(changes.iter().map(Change::path).collect::<Vec<_>>(), vec![
+            "ports[1]",
+            "ports_extra"
+        ]);
+
+        let changes = Value::Integer(1).diff(&Value::String("1".to_string()));
+        assert_eq!(changes, vec![Change::Modified {
+            path: String::new(),
+            old:  Value::Integer(1),
+            new:  Value::String("1".to_string()),
+        }]);
+    }
+
+    #[test]
+    fn test_diff_table_replaced_by_scalar() {
+        let old = table(&[
+            ("db.url", Value::String("pg://".to_string())),
+            ("db.pool", Value::Integer(4)),
+        ]);
+        let new = table(&[("db", Value::String("pg://".to_string()))]);
+
+        assert_eq!(old.diff(&new), vec![
+            Change::Removed {
+                path:  "db.pool".to_string(),
+                value: Value::Integer(4),
+            },
+            Change::Removed {
+                path:  "db.url".to_string(),
+                value: Value::String("pg://".to_string()),
+            },
+            Change::Added {
+                path:  "db".to_string(),
+                value: Value::String("pg://".to_string()),
+            },
+        ]);
+        assert_eq!(new.diff(&old), vec![
+            Change::Removed {
+                path:  "db".to_string(),
+                value: Value::String("pg://".to_string()),
+            },
+            Change::Added {
+                path:  "db.pool".to_string(),
+                value: Value::Integer(4),
+            },
+            Change::Added {
+                path:  "db.url".to_string(),
+                value: Value::String("pg://".to_string()),
+            },
+        ]);
+    }
+}
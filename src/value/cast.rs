@@ -1,5 +1,6 @@
 use super::{
     Array,
+    Datetime,
     Table,
     Value,
 };
@@ -18,14 +19,18 @@ impl TryFrom<Value> for String {
             Value::Null => Ok(Self::new()),
             Value::Boolean(b) => Ok(b.to_string()),
             Value::Integer(i) => Ok(i.to_string()),
+            Value::UInteger(u) => Ok(u.to_string()),
+            Value::Integer128(i) => Ok(i.to_string()),
+            Value::UInteger128(u) => Ok(u.to_string()),
             Value::Float(f) => Ok(f.to_string()),
             Value::String(s) => Ok(s),
-            Value::Array(a) => Err(Error::new_cast_error(
-                format!("{a:?}"),
+            Value::Datetime(d) => Ok(d.to_string()),
+            Value::Array(_) => Err(Error::new_cast_error(
+                "array".to_string(),
                 "Cannot cast array to string".to_string(),
             )),
-            Value::Table(t) => Err(Error::new_cast_error(
-                format!("{t:?}"),
+            Value::Table(_) => Err(Error::new_cast_error(
+                "table".to_string(),
                 "Cannot cast table to string".to_string(),
             )),
         }
@@ -40,20 +45,73 @@ impl TryFrom<&Value> for String {
             Value::Null => Ok(Self::new()),
             Value::Boolean(b) => Ok(b.to_string()),
             Value::Integer(i) => Ok(i.to_string()),
+            Value::UInteger(u) => Ok(u.to_string()),
+            Value::Integer128(i) => Ok(i.to_string()),
+            Value::UInteger128(u) => Ok(u.to_string()),
             Value::Float(f) => Ok(f.to_string()),
             Value::String(s) => Ok(s.clone()),
-            Value::Array(a) => Err(Error::new_cast_error(
-                format!("{a:?}"),
+            Value::Datetime(d) => Ok(d.to_string()),
+            Value::Array(_) => Err(Error::new_cast_error(
+                "array".to_string(),
                 "Cannot cast array to string".to_string(),
             )),
-            Value::Table(t) => Err(Error::new_cast_error(
-                format!("{t:?}"),
+            Value::Table(_) => Err(Error::new_cast_error(
+                "table".to_string(),
                 "Cannot cast table to string".to_string(),
             )),
         }
     }
 }
 
+/// Converts between integer types, failing instead of wrapping when the value
+/// is out of range for the target type.
+fn cast_integer<T, I>(i: I) -> Result<T, Error>
+where
+    T: TryFrom<I>,
+    I: Copy,
+{
+    T::try_from(i).map_err(|_e| {
+        Error::new_cast_error(
+            "integer".to_string(),
+            format!("Integer out of range for {}", std::any::type_name::<T>()),
+        )
+    })
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Self::Integer(i)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(u: u64) -> Self {
+        i64::try_from(u).map_or(Self::UInteger(u), Self::Integer)
+    }
+}
+
+impl From<i128> for Value {
+    fn from(i: i128) -> Self {
+        if let Ok(i) = i64::try_from(i) {
+            Self::Integer(i)
+        } else if let Ok(u) = u64::try_from(i) {
+            Self::UInteger(u)
+        } else {
+            Self::Integer128(i)
+        }
+    }
+}
+
+impl From<u128> for Value {
+    fn from(u: u128) -> Self {
+        if let Ok(u) = u64::try_from(u) {
+            Self::from(u)
+        } else {
+            i128::try_from(u).map_or(Self::UInteger128(u), Self::Integer128)
+        }
+    }
+}
+
 /// Macro to implement `TryFrom<Value>` for integer types.
 /// Handles conversion from all `Value` variants, with specific errors for
 /// non-convertible types.
@@ -66,12 +124,14 @@ macro_rules! impl_try_from_value_for_integer {
                 match value {
                     Value::Null => Ok(0 as Self),
                     Value::Boolean(b) => Ok(b.into()),
-                    #[allow(clippy::cast_lossless)]
-                    Value::Integer(i) => Ok(i as Self),
+                    Value::Integer(i) => cast_integer(i),
+                    Value::UInteger(u) => cast_integer(u),
+                    Value::Integer128(i) => cast_integer(i),
+                    Value::UInteger128(u) => cast_integer(u),
                     Value::Float(f) => Ok(f as Self),
                     Value::String(s) => s.parse().map_err(|_e| {
                         Error::new_cast_error(
-                            s,
+                            "string".to_string(),
                             format!(
                                 "Cannot cast string to {}",
                                 stringify!($type)
@@ -79,6 +139,10 @@ macro_rules! impl_try_from_value_for_integer {
                             .to_string(),
                         )
                     }),
+                    Value::Datetime(_) => Err(Error::new_cast_error(
+                        "datetime".to_string(),
+                        stringify!($type).to_string(),
+                    )),
                     Value::Array(_) => Err(Error::new_cast_error(
                         "array".to_string(),
                         stringify!($type).to_string(),
@@ -104,15 +168,18 @@ macro_rules! impl_try_from_value_for_float {
             fn try_from(value: Value) -> Result<Self, Self::Error> {
                 match value {
                     Value::Null => Ok(0.0 as Self),
-                    Value::Boolean(b) => Err(Error::new_cast_error(
-                        b.to_string(),
+                    Value::Boolean(_) => Err(Error::new_cast_error(
+                        "boolean".to_string(),
                         stringify!($type).to_string(),
                     )),
                     Value::Integer(i) => Ok(i as Self),
+                    Value::UInteger(u) => Ok(u as Self),
+                    Value::Integer128(i) => Ok(i as Self),
+                    Value::UInteger128(u) => Ok(u as Self),
                     Value::Float(f) => Ok(f as Self),
                     Value::String(s) => s.parse().map_err(|_e| {
                         Error::new_cast_error(
-                            s,
+                            "string".to_string(),
                             format!(
                                 "Cannot cast string to {}",
                                 stringify!($type)
@@ -120,6 +187,10 @@ macro_rules! impl_try_from_value_for_float {
                             .to_string(),
                         )
                     }),
+                    Value::Datetime(_) => Err(Error::new_cast_error(
+                        "datetime".to_string(),
+                        stringify!($type).to_string(),
+                    )),
                     Value::Array(_) => Err(Error::new_cast_error(
                         "array".to_string(),
                         stringify!($type).to_string(),
@@ -146,11 +217,14 @@ macro_rules! impl_try_from_value_for_uinteger {
                 match value {
                     Value::Null => Ok(0 as Self),
                     Value::Boolean(b) => Ok(b.into()),
-                    Value::Integer(i) => Ok(i as Self),
+                    Value::Integer(i) => cast_integer(i),
+                    Value::UInteger(u) => cast_integer(u),
+                    Value::Integer128(i) => cast_integer(i),
+                    Value::UInteger128(u) => cast_integer(u),
                     Value::Float(f) => Ok(f as Self),
                     Value::String(s) => s.parse().map_err(|_e| {
                         Error::new_cast_error(
-                            s,
+                            "string".to_string(),
                             format!(
                                 "Cannot cast string to {}",
                                 stringify!($type)
@@ -158,6 +232,10 @@ macro_rules! impl_try_from_value_for_uinteger {
                             .to_string(),
                         )
                     }),
+                    Value::Datetime(_) => Err(Error::new_cast_error(
+                        "datetime".to_string(),
+                        stringify!($type).to_string(),
+                    )),
                     Value::Array(_) => Err(Error::new_cast_error(
                         "array".to_string(),
                         stringify!($type).to_string(),
@@ -172,6 +250,23 @@ macro_rules! impl_try_from_value_for_uinteger {
     };
 }
 
+/// Attempts to convert a `Value` into a `Datetime`.
+/// Strings are parsed as RFC 3339, other variants are rejected.
+impl TryFrom<Value> for Datetime {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Datetime(d) => Ok(d),
+            Value::String(s) => s.parse(),
+            _ => Err(Error::new_cast_error(
+                value.value_type().to_string(),
+                format!("Cannot cast {} to datetime", value.value_type()),
+            )),
+        }
+    }
+}
+
 /// Attempts to convert a `Value` into a `bool`.
 /// Handles conversion from all `Value` variants, with specific errors for
 /// non-convertible types.
@@ -183,21 +278,28 @@ impl TryFrom<Value> for bool {
             Value::Null => Ok(false),
             Value::Boolean(b) => Ok(b),
             Value::Integer(i) => Ok(i != 0),
+            Value::UInteger(u) => Ok(u != 0),
+            Value::Integer128(i) => Ok(i != 0),
+            Value::UInteger128(u) => Ok(u != 0),
             Value::Float(f) => Ok(f != 0.0),
             Value::String(s) => match s.as_str() {
                 "true" | "1" | "yes" | "on" => Ok(true),
                 "false" | "0" | "no" | "off" => Ok(false),
                 _ => Err(Error::new_cast_error(
-                    s,
+                    "string".to_string(),
                     "Cannot cast string to bool".to_string(),
                 )),
             },
-            Value::Array(a) => Err(Error::new_cast_error(
-                format!("{a:?}"),
+            Value::Datetime(_) => Err(Error::new_cast_error(
+                "datetime".to_string(),
+                "Cannot cast datetime to bool".to_string(),
+            )),
+            Value::Array(_) => Err(Error::new_cast_error(
+                "array".to_string(),
                 "Cannot cast array to bool".to_string(),
             )),
-            Value::Table(t) => Err(Error::new_cast_error(
-                format!("{t:?}"),
+            Value::Table(_) => Err(Error::new_cast_error(
+                "table".to_string(),
                 "Cannot cast table to bool".to_string(),
             )),
         }
@@ -212,21 +314,28 @@ impl TryFrom<&Value> for bool {
             Value::Null => Ok(false),
             Value::Boolean(b) => Ok(*b),
             Value::Integer(i) => Ok(*i != 0),
+            Value::UInteger(u) => Ok(*u != 0),
+            Value::Integer128(i) => Ok(*i != 0),
+            Value::UInteger128(u) => Ok(*u != 0),
             Value::Float(f) => Ok(*f != 0.0),
             Value::String(s) => match s.as_str() {
                 "true" | "1" | "yes" | "on" => Ok(true),
                 "false" | "0" | "no" | "off" => Ok(false),
                 _ => Err(Error::new_cast_error(
-                    s.clone(),
+                    "string".to_string(),
                     "Cannot cast string to bool".to_string(),
                 )),
             },
-            Value::Array(a) => Err(Error::new_cast_error(
-                format!("{a:?}"),
+            Value::Datetime(_) => Err(Error::new_cast_error(
+                "datetime".to_string(),
+                "Cannot cast datetime to bool".to_string(),
+            )),
+            Value::Array(_) => Err(Error::new_cast_error(
+                "array".to_string(),
                 "Cannot cast array to bool".to_string(),
             )),
-            Value::Table(t) => Err(Error::new_cast_error(
-                format!("{t:?}"),
+            Value::Table(_) => Err(Error::new_cast_error(
+                "table".to_string(),
                 "Cannot cast table to bool".to_string(),
             )),
         }
@@ -242,12 +351,9 @@ impl TryFrom<Value> for Array {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Null => Ok(Self::new()),
-            Value::Boolean(b) => Ok(vec![Value::Boolean(b)]),
-            Value::Integer(i) => Ok(vec![Value::Integer(i)]),
-            Value::Float(f) => Ok(vec![Value::Float(f)]),
-            Value::String(s) => Ok(vec![Value::String(s)]),
             Value::Array(a) => Ok(a),
             Value::Table(t) => t.try_into(),
+            _ => Ok(vec![value]),
         }
     }
 }
@@ -258,12 +364,9 @@ impl TryFrom<&Value> for Array {
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
             Value::Null => Ok(Self::new()),
-            Value::Boolean(b) => Ok(vec![Value::Boolean(*b)]),
-            Value::Integer(i) => Ok(vec![Value::Integer(*i)]),
-            Value::Float(f) => Ok(vec![Value::Float(*f)]),
-            Value::String(s) => Ok(vec![Value::String(s.clone())]),
             Value::Array(a) => Ok(a.clone()),
             Value::Table(t) => t.clone().try_into(),
+            _ => Ok(vec![value.clone()]),
         }
     }
 }
@@ -340,14 +443,11 @@ impl<T: TryFrom<Value, Error = Error>> TryFrom<Value> for Vec<T> {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Null => Ok(Self::new()),
-            Value::Boolean(b) => Ok(vec![T::try_from(Value::Boolean(b))?]),
-            Value::Integer(i) => Ok(vec![T::try_from(Value::Integer(i))?]),
-            Value::Float(f) => Ok(vec![T::try_from(Value::Float(f))?]),
-            Value::String(s) => Ok(vec![T::try_from(Value::String(s))?]),
             Value::Array(a) => a.into_iter().map(T::try_from).collect(),
             Value::Table(t) => {
                 t.into_iter().map(|(_, v)| T::try_from(v)).collect()
             }
+            _ => Ok(vec![T::try_from(value)?]),
         }
     }
 }
@@ -358,18 +458,13 @@ impl<T: TryFrom<Value, Error = Error>> TryFrom<&Value> for Vec<T> {
     fn try_from(value: &Value) -> Result<Self, Self::Error> {
         match value {
             Value::Null => Ok(Self::new()),
-            Value::Boolean(b) => Ok(vec![T::try_from(Value::Boolean(*b))?]),
-            Value::Integer(i) => Ok(vec![T::try_from(Value::Integer(*i))?]),
-            Value::Float(f) => Ok(vec![T::try_from(Value::Float(*f))?]),
-            Value::String(s) => {
-                Ok(vec![T::try_from(Value::String(s.clone()))?])
-            }
             Value::Array(a) => {
                 a.iter().map(|v| T::try_from(v.clone())).collect()
             }
             Value::Table(t) => {
                 t.iter().map(|(_, v)| T::try_from(v.clone())).collect()
             }
+            _ => Ok(vec![T::try_from(value.clone())?]),
         }
     }
 }
@@ -454,7 +549,9 @@ mod tests {
         assert_eq!(i64::try_from(Value::Integer(42))?, 42);
         assert_eq!(i64::try_from(Value::Float(0.618))?, 0);
         assert_eq!(i64::try_from(Value::String("42".to_string()))?, 42);
-        assert!(i64::try_from(Value::String("test".to_string())).is_err());
+        let err = i64::try_from(Value::String("hunter2".to_string()))
+            .expect_err("not a number");
+        assert!(!err.to_string().contains("hunter2"), "{err}");
         assert!(i64::try_from(Value::Array(vec![])).is_err());
         assert!(i64::try_from(Value::Table(Map::default())).is_err());
         Ok(())
@@ -493,6 +590,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_large_integer_conversion() -> anyhow::Result<()> {
+        assert_eq!(Value::from(42_u64), Value::Integer(42));
+        assert_eq!(Value::from(u64::MAX), Value::UInteger(u64::MAX));
+        assert_eq!(
+            Value::from(i128::from(u64::MAX)),
+            Value::UInteger(u64::MAX)
+        );
+        assert_eq!(Value::from(i128::MIN), Value::Integer128(i128::MIN));
+        assert_eq!(Value::from(u128::MAX), Value::UInteger128(u128::MAX));
+
+        assert_eq!(u64::try_from(Value::UInteger(u64::MAX))?, u64::MAX);
+        assert_eq!(u128::try_from(Value::UInteger128(u128::MAX))?, u128::MAX);
+        assert_eq!(
+            i128::try_from(Value::UInteger(u64::MAX))?,
+            18_446_744_073_709_551_615
+        );
+        assert_eq!(
+            String::try_from(Value::UInteger(u64::MAX))?,
+            "18446744073709551615"
+        );
+        assert!(bool::try_from(Value::UInteger(u64::MAX))?);
+
+        assert!(u64::try_from(Value::Integer(-1)).is_err());
+        assert!(i64::try_from(Value::UInteger(u64::MAX)).is_err());
+        assert!(u8::try_from(Value::Integer(256)).is_err());
+        assert!(u64::try_from(Value::UInteger128(u128::MAX)).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_bool_conversion() -> anyhow::Result<()> {
         assert!(!bool::try_from(Value::Null)?);
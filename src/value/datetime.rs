@@ -0,0 +1,754 @@
+use std::{
+    fmt::{
+        self,
+        Display,
+        Formatter,
+    },
+    str::FromStr,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+    de,
+};
+
+use crate::{
+    Error,
+    Result,
+};
+
+/// Name of the newtype struct used to carry a `Datetime` through serde
+/// without losing its type when serializing into a `Value`.
+pub(crate) const DATETIME_NAME: &str = "$__realme_private_Datetime";
+
+/// Representation of a datetime value, as found in TOML and YAML.
+///
+/// Depending on which parts are present this is an offset datetime
+/// (`1979-05-27T07:32:00Z`), a local datetime (`1979-05-27T07:32:00`), a local
+/// date (`1979-05-27`) or a local time (`07:32:00.999`).
+///
+/// A `Datetime` deserializes as its RFC 3339 string, so it can be read into
+/// `chrono` types directly, and into `time` types with
+/// `time::serde::rfc3339`. With the `chrono` or `time` feature enabled, it can
+/// also be converted with `TryFrom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Datetime {
+    /// The date part, if any.
+    pub date:   Option<Date>,
+    /// The time part, if any.
+    pub time:   Option<Time>,
+    /// The offset from UTC, only present along with both date and time.
+    pub offset: Option<Offset>,
+}
+
+/// A calendar date, e.g. `1979-05-27`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub year:  u16,
+    pub month: u8,
+    pub day:   u8,
+}
+
+/// A time of day with nanosecond precision, e.g. `07:32:00.999999`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time {
+    pub hour:       u8,
+    pub minute:     u8,
+    pub second:     u8,
+    pub nanosecond: u32,
+}
+
+/// An offset from UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Offset {
+    /// UTC, written as `Z`.
+    Z,
+    /// A fixed offset in minutes, e.g. `+08:00` is `480`.
+    Custom { minutes: i16 },
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond != 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Offset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Z => f.write_str("Z"),
+            Self::Custom { minutes } => {
+                let sign = if minutes < 0 { '-' } else { '+' };
+                let minutes = minutes.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl Display for Datetime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(date) = &self.date {
+            write!(f, "{date}")?;
+        }
+        if let Some(time) = &self.time {
+            if self.date.is_some() {
+                f.write_str("T")?;
+            }
+            write!(f, "{time}")?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, "{offset}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Datetime {
+    type Err = Error;
+
+    /// Parses an RFC 3339 datetime, or one of the partial forms TOML allows.
+    ///
+    /// The date and time may also be separated by a space or a lowercase `t`.
+    fn from_str(s: &str) -> Result<Self> {
+        parse_datetime(s).ok_or_else(|| {
+            Error::new_parse_error(
+                "datetime".to_string(),
+                "Invalid datetime, expected RFC 3339 format".to_string(),
+            )
+        })
+    }
+}
+
+/// Reads exactly `n` ASCII digits from the front of `s`.
+fn digits(s: &mut &str, n: usize) -> Option<u32> {
+    let head = s.get(..n)?;
+    if !head.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    *s = &s[n..];
+    head.parse().ok()
+}
+
+/// Consumes `ch` from the front of `s`.
+fn expect(s: &mut &str, ch: char) -> Option<()> {
+    *s = s.strip_prefix(ch)?;
+    Some(())
+}
+
+const fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) &&
+            (!year.is_multiple_of(100) || year.is_multiple_of(400)) =>
+        {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_date(s: &mut &str) -> Option<Date> {
+    let year = digits(s, 4)?;
+    expect(s, '-')?;
+    let month = digits(s, 2)?;
+    expect(s, '-')?;
+    let day = digits(s, 2)?;
+    if !(1..=12).contains(&month) ||
+        !(1..=days_in_month(year, month)).contains(&day)
+    {
+        return None;
+    }
+    Some(Date {
+        year:  year as u16,
+        month: month as u8,
+        day:   day as u8,
+    })
+}
+
+fn parse_time(s: &mut &str) -> Option<Time> {
+    let hour = digits(s, 2)?;
+    expect(s, ':')?;
+    let minute = digits(s, 2)?;
+    expect(s, ':')?;
+    // 60 is allowed for leap seconds
+    let second = digits(s, 2)?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let mut nanosecond = 0;
+    if expect(s, '.').is_some() {
+        let len = s.bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        // Digits beyond nanosecond precision are truncated
+        let fraction = &s[..len.min(9)];
+        nanosecond = fraction.parse::<u32>().ok()? *
+            10u32.pow(9 - fraction.len() as u32);
+        *s = &s[len..];
+    }
+    Some(Time {
+        hour: hour as u8,
+        minute: minute as u8,
+        second: second as u8,
+        nanosecond,
+    })
+}
+
+fn parse_offset(s: &mut &str) -> Option<Offset> {
+    if expect(s, 'Z').is_some() || expect(s, 'z').is_some() {
+        return Some(Offset::Z);
+    }
+    let sign = if expect(s, '+').is_some() {
+        1
+    } else {
+        expect(s, '-')?;
+        -1
+    };
+    let hours = digits(s, 2)?;
+    expect(s, ':')?;
+    let minutes = digits(s, 2)?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some(Offset::Custom {
+        minutes: sign * (hours * 60 + minutes) as i16,
+    })
+}
+
+/// Parses a datetime like `Datetime::from_str`, without building an error
+/// when `s` is not one.
+pub(crate) fn parse_datetime(mut s: &str) -> Option<Datetime> {
+    let s = &mut s;
+    let mut datetime = Datetime {
+        date:   None,
+        time:   None,
+        offset: None,
+    };
+    if s.as_bytes().get(2) == Some(&b':') {
+        datetime.time = Some(parse_time(s)?);
+    } else {
+        datetime.date = Some(parse_date(s)?);
+        if let Some(rest) = s
+            .strip_prefix('T')
+            .or_else(|| s.strip_prefix('t'))
+            .or_else(|| s.strip_prefix(' '))
+        {
+            *s = rest;
+            datetime.time = Some(parse_time(s)?);
+            if !s.is_empty() {
+                datetime.offset = Some(parse_offset(s)?);
+            }
+        }
+    }
+    s.is_empty().then_some(datetime)
+}
+
+impl Serialize for Datetime {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(DATETIME_NAME, &self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Datetime {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        struct DatetimeVisitor;
+
+        impl de::Visitor<'_> for DatetimeVisitor {
+            type Value = Datetime;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("an RFC 3339 datetime string")
+            }
+
+            fn visit_str<E: de::Error>(
+                self,
+                v: &str,
+            ) -> std::result::Result<Datetime, E> {
+                v.parse().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_str(DatetimeVisitor)
+    }
+}
+
+/// Converts the year of a `chrono` or `time` date, which may be negative or
+/// past 65535.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn year(year: i32) -> Result<u16> {
+    u16::try_from(year).map_err(|e| {
+        Error::new_cast_error("date".to_string(), format!("Invalid year: {e}"))
+    })
+}
+
+/// Names an offset of `minutes` from UTC, which is `Z` for UTC itself.
+#[cfg(any(feature = "chrono", feature = "time"))]
+const fn offset(minutes: i16) -> Offset {
+    if minutes == 0 {
+        Offset::Z
+    } else {
+        Offset::Custom { minutes }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use chrono::{
+        DateTime,
+        Datelike,
+        FixedOffset,
+        NaiveDate,
+        NaiveDateTime,
+        NaiveTime,
+        Timelike,
+    };
+
+    use super::*;
+
+    fn missing(target: &str) -> Error {
+        Error::new_cast_error(
+            "datetime".to_string(),
+            format!("Cannot cast datetime to {target}"),
+        )
+    }
+
+    impl TryFrom<Date> for NaiveDate {
+        type Error = Error;
+
+        fn try_from(date: Date) -> Result<Self> {
+            Self::from_ymd_opt(
+                i32::from(date.year),
+                u32::from(date.month),
+                u32::from(date.day),
+            )
+            .ok_or_else(|| {
+                Error::new_cast_error(
+                    "date".to_string(),
+                    "Cannot cast date to NaiveDate".to_string(),
+                )
+            })
+        }
+    }
+
+    impl TryFrom<Time> for NaiveTime {
+        type Error = Error;
+
+        fn try_from(time: Time) -> Result<Self> {
+            Self::from_hms_nano_opt(
+                u32::from(time.hour),
+                u32::from(time.minute),
+                u32::from(time.second),
+                time.nanosecond,
+            )
+            .ok_or_else(|| {
+                Error::new_cast_error(
+                    "time".to_string(),
+                    "Cannot cast time to NaiveTime".to_string(),
+                )
+            })
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveDate {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            datetime
+                .date
+                .ok_or_else(|| missing("NaiveDate"))?
+                .try_into()
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveTime {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            datetime
+                .time
+                .ok_or_else(|| missing("NaiveTime"))?
+                .try_into()
+        }
+    }
+
+    impl TryFrom<Datetime> for NaiveDateTime {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            match (datetime.date, datetime.time) {
+                (Some(date), Some(time)) => {
+                    Ok(Self::new(date.try_into()?, time.try_into()?))
+                }
+                _ => Err(missing("NaiveDateTime")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for DateTime<FixedOffset> {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            let naive = NaiveDateTime::try_from(datetime)?;
+            let seconds = match datetime.offset {
+                Some(Offset::Z) => 0,
+                Some(Offset::Custom { minutes }) => i32::from(minutes) * 60,
+                None => return Err(missing("DateTime")),
+            };
+            FixedOffset::east_opt(seconds)
+                .and_then(|offset| naive.and_local_timezone(offset).single())
+                .ok_or_else(|| missing("DateTime"))
+        }
+    }
+
+    impl TryFrom<NaiveDate> for Date {
+        type Error = Error;
+
+        fn try_from(date: NaiveDate) -> Result<Self> {
+            Ok(Self {
+                year:  year(date.year())?,
+                month: date.month() as u8,
+                day:   date.day() as u8,
+            })
+        }
+    }
+
+    impl From<NaiveTime> for Time {
+        fn from(time: NaiveTime) -> Self {
+            Self {
+                hour:       time.hour() as u8,
+                minute:     time.minute() as u8,
+                second:     time.second() as u8,
+                nanosecond: time.nanosecond(),
+            }
+        }
+    }
+
+    impl TryFrom<NaiveDate> for Datetime {
+        type Error = Error;
+
+        fn try_from(date: NaiveDate) -> Result<Self> {
+            Ok(Self {
+                date:   Some(date.try_into()?),
+                time:   None,
+                offset: None,
+            })
+        }
+    }
+
+    impl From<NaiveTime> for Datetime {
+        fn from(time: NaiveTime) -> Self {
+            Self {
+                date:   None,
+                time:   Some(time.into()),
+                offset: None,
+            }
+        }
+    }
+
+    impl TryFrom<NaiveDateTime> for Datetime {
+        type Error = Error;
+
+        fn try_from(datetime: NaiveDateTime) -> Result<Self> {
+            Ok(Self {
+                date:   Some(datetime.date().try_into()?),
+                time:   Some(datetime.time().into()),
+                offset: None,
+            })
+        }
+    }
+
+    impl<Tz: chrono::TimeZone> TryFrom<DateTime<Tz>> for Datetime {
+        type Error = Error;
+
+        fn try_from(datetime: DateTime<Tz>) -> Result<Self> {
+            let minutes =
+                chrono::Offset::fix(datetime.offset()).local_minus_utc() / 60;
+            Ok(Self {
+                offset: Some(offset(minutes as i16)),
+                ..datetime.naive_local().try_into()?
+            })
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use time::{
+        OffsetDateTime,
+        PrimitiveDateTime,
+        UtcOffset,
+    };
+
+    use super::*;
+
+    fn missing(target: &str) -> Error {
+        Error::new_cast_error(
+            "datetime".to_string(),
+            format!("Cannot cast datetime to {target}"),
+        )
+    }
+
+    impl TryFrom<Date> for time::Date {
+        type Error = Error;
+
+        fn try_from(date: Date) -> Result<Self> {
+            time::Month::try_from(date.month)
+                .and_then(|month| {
+                    Self::from_calendar_date(
+                        i32::from(date.year),
+                        month,
+                        date.day,
+                    )
+                })
+                .map_err(|e| {
+                    Error::new_cast_error("date".to_string(), e.to_string())
+                })
+        }
+    }
+
+    impl TryFrom<Time> for time::Time {
+        type Error = Error;
+
+        fn try_from(time: Time) -> Result<Self> {
+            Self::from_hms_nano(
+                time.hour,
+                time.minute,
+                time.second,
+                time.nanosecond,
+            )
+            .map_err(|e| {
+                Error::new_cast_error("time".to_string(), e.to_string())
+            })
+        }
+    }
+
+    impl TryFrom<Datetime> for time::Date {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            datetime.date.ok_or_else(|| missing("Date"))?.try_into()
+        }
+    }
+
+    impl TryFrom<Datetime> for time::Time {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            datetime.time.ok_or_else(|| missing("Time"))?.try_into()
+        }
+    }
+
+    impl TryFrom<Datetime> for PrimitiveDateTime {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            match (datetime.date, datetime.time) {
+                (Some(date), Some(time)) => {
+                    Ok(Self::new(date.try_into()?, time.try_into()?))
+                }
+                _ => Err(missing("PrimitiveDateTime")),
+            }
+        }
+    }
+
+    impl TryFrom<Datetime> for OffsetDateTime {
+        type Error = Error;
+
+        fn try_from(datetime: Datetime) -> Result<Self> {
+            let primitive = PrimitiveDateTime::try_from(datetime)?;
+            let seconds = match datetime.offset {
+                Some(Offset::Z) => 0,
+                Some(Offset::Custom { minutes }) => i32::from(minutes) * 60,
+                None => return Err(missing("OffsetDateTime")),
+            };
+            let offset =
+                UtcOffset::from_whole_seconds(seconds).map_err(|e| {
+                    Error::new_cast_error("datetime".to_string(), e.to_string())
+                })?;
+            Ok(primitive.assume_offset(offset))
+        }
+    }
+
+    impl TryFrom<time::Date> for Date {
+        type Error = Error;
+
+        fn try_from(date: time::Date) -> Result<Self> {
+            Ok(Self {
+                year:  year(date.year())?,
+                month: u8::from(date.month()),
+                day:   date.day(),
+            })
+        }
+    }
+
+    impl From<time::Time> for Time {
+        fn from(time: time::Time) -> Self {
+            Self {
+                hour:       time.hour(),
+                minute:     time.minute(),
+                second:     time.second(),
+                nanosecond: time.nanosecond(),
+            }
+        }
+    }
+
+    impl TryFrom<PrimitiveDateTime> for Datetime {
+        type Error = Error;
+
+        fn try_from(datetime: PrimitiveDateTime) -> Result<Self> {
+            Ok(Self {
+                date:   Some(datetime.date().try_into()?),
+                time:   Some(datetime.time().into()),
+                offset: None,
+            })
+        }
+    }
+
+    impl TryFrom<OffsetDateTime> for Datetime {
+        type Error = Error;
+
+        fn try_from(datetime: OffsetDateTime) -> Result<Self> {
+            Ok(Self {
+                date:   Some(datetime.date().try_into()?),
+                time:   Some(datetime.time().into()),
+                offset: Some(offset(datetime.offset().whole_minutes())),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_parse_and_display() -> anyhow::Result<()> {
+        for s in [
+            "1979-05-27T07:32:00Z",
+            "1979-05-27T00:32:00.999999-07:00",
+            "1979-05-27T07:32:00",
+            "1979-05-27",
+            "07:32:00.5",
+        ] {
+            assert_eq!(s.parse::<Datetime>()?.to_string(), s);
+        }
+        let dt: Datetime = "1979-05-27 07:32:00.123456789+08:00".parse()?;
+        assert_eq!(dt.time.map(|t| t.nanosecond), Some(123_456_789));
+        assert_eq!(dt.offset, Some(Offset::Custom { minutes: 480 }));
+        assert_eq!(dt.to_string(), "1979-05-27T07:32:00.123456789+08:00");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [
+            "",
+            "hello",
+            "1979-13-01",
+            "1979-02-30",
+            "1979-05-27T25:00:00",
+            "1979-05-27T07:32:00+8",
+            "07:32:00Z",
+            "2006-01-02T15:04:05Z07:00",
+        ] {
+            assert!(s.parse::<Datetime>().is_err(), "{s}");
+        }
+        let err = "hunter2".parse::<Datetime>().expect_err("not a datetime");
+        assert!(!err.to_string().contains("hunter2"), "{err}");
+    }
+
+    #[test]
+    fn test_value_roundtrip() -> anyhow::Result<()> {
+        let dt: Datetime = "1979-05-27T07:32:00Z".parse()?;
+        let value = Value::try_serialize(&dt)?;
+        assert_eq!(value, Value::Datetime(dt));
+        assert_eq!(Value::try_serialize(&value)?, value);
+        assert_eq!(value.clone().try_deserialize::<Datetime>()?, dt);
+        assert_eq!(value.try_deserialize::<String>()?, "1979-05-27T07:32:00Z");
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_into_chrono() -> anyhow::Result<()> {
+        let value = Value::Datetime("1979-05-27T07:32:00.5-07:00".parse()?);
+        let parsed: chrono::DateTime<chrono::FixedOffset> =
+            value.clone().try_deserialize()?;
+        assert_eq!(parsed.to_rfc3339(), "1979-05-27T07:32:00.500-07:00");
+        let utc: chrono::DateTime<chrono::Utc> = value.try_deserialize()?;
+        assert_eq!(utc.to_rfc3339(), "1979-05-27T14:32:00.500+00:00");
+        let date: chrono::NaiveDate =
+            Value::Datetime("1979-05-27".parse()?).try_deserialize()?;
+        assert_eq!(date.to_string(), "1979-05-27");
+        Ok(())
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_conversion() -> anyhow::Result<()> {
+        let dt: Datetime = "1979-05-27T07:32:00.25+08:00".parse()?;
+        let parsed = chrono::DateTime::<chrono::FixedOffset>::try_from(dt)?;
+        assert_eq!(parsed.to_rfc3339(), "1979-05-27T07:32:00.250+08:00");
+        assert_eq!(Datetime::try_from(parsed)?, dt);
+        assert!(chrono::NaiveTime::try_from(dt).is_ok());
+        let local: Datetime = "1979-05-27T07:32:00".parse()?;
+        assert!(
+            chrono::DateTime::<chrono::FixedOffset>::try_from(local).is_err()
+        );
+        let utc = parsed.with_timezone(&chrono::Utc);
+        assert_eq!(
+            Datetime::try_from(utc)?.to_string(),
+            "1979-05-26T23:32:00.25Z"
+        );
+        let ancient = chrono::NaiveDate::from_ymd_opt(-1, 1, 1).expect("date");
+        assert!(Datetime::try_from(ancient).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_conversion() -> anyhow::Result<()> {
+        let dt: Datetime = "1979-05-27T07:32:00.25-07:30".parse()?;
+        let parsed = time::OffsetDateTime::try_from(dt)?;
+        assert_eq!(parsed.offset().whole_minutes(), -450);
+        assert_eq!(parsed.nanosecond(), 250_000_000);
+        assert_eq!(Datetime::try_from(parsed)?, dt);
+        let date = time::Date::try_from(Datetime::from_str("1979-05-27")?)?;
+        assert_eq!(date.day(), 27);
+        let utc = parsed.to_offset(time::UtcOffset::UTC);
+        assert_eq!(
+            Datetime::try_from(utc)?.to_string(),
+            "1979-05-27T15:02:00.25Z"
+        );
+        let ancient =
+            time::Date::from_calendar_date(-1, time::Month::January, 1)?;
+        assert!(
+            Datetime::try_from(time::PrimitiveDateTime::new(
+                ancient,
+                time::Time::MIDNIGHT
+            ))
+            .is_err()
+        );
+        Ok(())
+    }
+}
@@ -10,10 +10,7 @@ use serde::{
     },
 };
 
-use super::{
-    Table,
-    Value,
-};
+use super::Value;
 use crate::Map;
 
 /// Represents a custom deserializer for `Value` type.
@@ -44,12 +41,16 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::Integer(value))
     }
 
-    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
-        if i64::try_from(value).is_ok() {
-            Ok(Value::Integer(value as i64))
-        } else {
-            Err(de::Error::custom("u64 value was too large"))
-        }
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_i128<E>(self, value: i128) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_u128<E>(self, value: u128) -> Result<Value, E> {
+        Ok(Value::from(value))
     }
 
     fn visit_u32<E>(self, value: u32) -> Result<Value, E> {
@@ -115,7 +116,11 @@ impl<'de> serde::Deserializer<'de> for Value {
         match self {
             Self::Null => visitor.visit_none(),
             Self::String(s) => visitor.visit_str(&s),
+            Self::Datetime(d) => visitor.visit_str(&d.to_string()),
             Self::Integer(i) => visitor.visit_i64(i),
+            Self::UInteger(u) => visitor.visit_u64(u),
+            Self::Integer128(i) => visitor.visit_i128(i),
+            Self::UInteger128(u) => visitor.visit_u128(u),
             Self::Boolean(b) => visitor.visit_bool(b),
             Self::Float(f) => visitor.visit_f64(f),
             Self::Array(a) => visitor.visit_seq(SeqDeserializer::new(a)),
@@ -162,60 +167,60 @@ impl<'de> serde::Deserializer<'de> for Value {
     where
         V: Visitor<'de>,
     {
-        let i: i64 = self
+        let i: i8 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_i8(i as i8)
+        visitor.visit_i8(i)
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let i: i64 = self
+        let i: i16 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_i16(i as i16)
+        visitor.visit_i16(i)
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let i: i64 = self
+        let i: i32 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_i32(i as i32)
+        visitor.visit_i32(i)
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let u: u64 = self
+        let u: u8 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_u8(u as u8)
+        visitor.visit_u8(u)
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let u: u64 = self
+        let u: u16 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_u16(u as u16)
+        visitor.visit_u16(u)
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let u: u64 = self
+        let u: u32 = self
             .try_into()
             .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
-        visitor.visit_u32(u as u32)
+        visitor.visit_u32(u)
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -228,6 +233,26 @@ impl<'de> serde::Deserializer<'de> for Value {
         visitor.visit_u64(u)
     }
 
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let i: i128 = self
+            .try_into()
+            .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
+        visitor.visit_i128(i)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let u: u128 = self
+            .try_into()
+            .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
+        visitor.visit_u128(u)
+    }
+
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
@@ -359,16 +384,15 @@ impl<'de> serde::Deserializer<'de> for Value {
         self,
         _name: &'static str,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom(format!(
-            "unsupported type for tuple struct: {}, value: {:?}",
-            self.value_type(),
-            self
-        )))
+        let seq = self
+            .try_into()
+            .map_err(|e: crate::Error| de::Error::custom(e.to_string()))?;
+        visitor.visit_seq(SeqDeserializer::new(seq))
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -414,15 +438,20 @@ impl<'de> serde::Deserializer<'de> for Value {
         V: Visitor<'de>,
     {
         match self {
-            Self::String(s) => {
-                visitor.visit_enum(EnumDeserializer::new(EnumVariant::Unit(s)))
+            Self::String(variant) => {
+                visitor.visit_enum(EnumDeserializer::new(variant, None))
             }
-            Self::Table(_) => Err(de::Error::custom(
-                "enum with tuple or struct variant is not supported"
-                    .to_string(),
-            )),
+            Self::Table(t) if t.len() == 1 => {
+                let (variant, value) =
+                    t.into_iter().next().expect("table has one entry");
+                visitor.visit_enum(EnumDeserializer::new(variant, Some(value)))
+            }
+            Self::Table(t) => Err(de::Error::custom(format!(
+                "expected a table with a single key for enum, got {} keys",
+                t.len()
+            ))),
             _ => Err(de::Error::custom(format!(
-                "expected a string, got {}, value: {:?}",
+                "expected a string or table, got {}, value: {:?}",
                 self.value_type(),
                 self
             ))),
@@ -457,8 +486,12 @@ impl<'de> serde::Deserializer<'de> for Value {
             Self::Null => visitor.visit_none(),
             Self::Boolean(b) => visitor.visit_bool(b),
             Self::Integer(i) => visitor.visit_i64(i),
+            Self::UInteger(u) => visitor.visit_u64(u),
+            Self::Integer128(i) => visitor.visit_i128(i),
+            Self::UInteger128(u) => visitor.visit_u128(u),
             Self::Float(f) => visitor.visit_f64(f),
             Self::String(s) => visitor.visit_str(&s),
+            Self::Datetime(d) => visitor.visit_str(&d.to_string()),
             Self::Array(a) => visitor.visit_seq(SeqDeserializer::new(a)),
             Self::Table(t) => visitor.visit_map(MapDeserializer::new(t)),
         }
@@ -555,68 +588,96 @@ impl SeqDeserializer {
     }
 }
 
-/// A helper struct to facilitate map deserialization.
+/// A helper struct to facilitate enum deserialization.
+///
+/// Enums are externally tagged: a unit variant is a plain string, and any
+/// other variant is a single-entry table `{ variant = content }`.
 struct EnumDeserializer {
-    variant: EnumVariant,
-}
-
-enum EnumVariant {
-    Unit(String),
-    #[allow(dead_code)]
-    Tuple(Table),
-    #[allow(dead_code)]
-    Struct(Table),
+    variant: String,
+    value:   Option<Value>,
 }
 
 impl EnumDeserializer {
-    const fn new(variant: EnumVariant) -> Self {
-        Self { variant }
+    const fn new(variant: String, value: Option<Value>) -> Self {
+        Self { variant, value }
     }
 }
 
-struct EnumVariantDeserializer;
+/// A helper struct to deserialize the content of an enum variant.
+struct VariantDeserializer {
+    value: Option<Value>,
+}
 
-impl<'de> de::VariantAccess<'de> for EnumVariantDeserializer {
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
     type Error = crate::errors::DeserializeError;
 
     fn unit_variant(self) -> Result<(), Self::Error> {
-        Ok(())
+        match self.value {
+            None | Some(Value::Null) => Ok(()),
+            Some(value) => Err(de::Error::custom(format!(
+                "expected a unit variant, got {}",
+                value.value_type()
+            ))),
+        }
     }
 
-    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Self::Error>
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
     where
         T: de::DeserializeSeed<'de>,
     {
-        Err(de::Error::custom("newtype variant is not supported"))
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom(
+                "expected a newtype variant, got a unit variant",
+            )),
+        }
     }
 
     fn tuple_variant<V>(
         self,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("tuple variant is not supported"))
+        match self.value {
+            Some(Value::Array(a)) => visitor.visit_seq(SeqDeserializer::new(a)),
+            Some(value) => Err(de::Error::custom(format!(
+                "expected an array for tuple variant, got {}",
+                value.value_type()
+            ))),
+            None => Err(de::Error::custom(
+                "expected a tuple variant, got a unit variant",
+            )),
+        }
     }
 
     fn struct_variant<V>(
         self,
         _fields: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(de::Error::custom("struct variant is not supported"))
+        match self.value {
+            Some(Value::Table(t)) => visitor.visit_map(MapDeserializer::new(t)),
+            Some(value) => Err(de::Error::custom(format!(
+                "expected a table for struct variant, got {}",
+                value.value_type()
+            ))),
+            None => Err(de::Error::custom(
+                "expected a struct variant, got a unit variant",
+            )),
+        }
     }
 }
 
 impl<'de> de::EnumAccess<'de> for EnumDeserializer {
     type Error = crate::errors::DeserializeError;
 
-    type Variant = EnumVariantDeserializer;
+    type Variant = VariantDeserializer;
 
     fn variant_seed<V>(
         self,
@@ -625,15 +686,9 @@ impl<'de> de::EnumAccess<'de> for EnumDeserializer {
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        let EnumVariant::Unit(key) = self.variant else {
-            return Err(de::Error::custom("not a unit variant".to_string()));
-        };
-
-        let val = seed.deserialize(key.into_deserializer())?;
-
-        let variant = EnumVariantDeserializer;
+        let val = seed.deserialize(self.variant.into_deserializer())?;
 
-        Ok((val, variant))
+        Ok((val, VariantDeserializer { value: self.value }))
     }
 }
 
@@ -750,6 +805,75 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_deserialize_large_integers() -> anyhow::Result<()> {
+        let result: u64 = Value::UInteger(u64::MAX).try_deserialize()?;
+        assert_eq!(result, u64::MAX);
+        let result: i128 = Value::Integer128(i128::MIN).try_deserialize()?;
+        assert_eq!(result, i128::MIN);
+        let result: u128 = Value::UInteger128(u128::MAX).try_deserialize()?;
+        assert_eq!(result, u128::MAX);
+        assert!(Value::Integer(300).try_deserialize::<u8>().is_err());
+        assert!(Value::Integer(-1).try_deserialize::<u32>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_humanized() -> anyhow::Result<()> {
+        use std::time::Duration;
+
+        use crate::HumanDuration;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Cache {
+            ttl:      HumanDuration,
+            interval: HumanDuration,
+            timeout:  HumanDuration,
+            size:     crate::ByteSize,
+            limit:    crate::ByteSize,
+        }
+
+        let value = Value::Table(Map::from_iter([
+            ("ttl".to_string(), Value::String("1h30m".to_string())),
+            ("interval".to_string(), Value::Integer(5)),
+            ("timeout".to_string(), Value::Float(1.5)),
+            ("size".to_string(), Value::String("512MiB".to_string())),
+            ("limit".to_string(), Value::Integer(4096)),
+        ]));
+        let cache: Cache = value.try_deserialize()?;
+        assert_eq!(cache, Cache {
+            ttl:      HumanDuration(Duration::from_secs(90 * 60)),
+            interval: HumanDuration(Duration::from_secs(5)),
+            timeout:  HumanDuration(Duration::from_millis(1500)),
+            size:     crate::ByteSize(512 * 1024 * 1024),
+            limit:    crate::ByteSize(4096),
+        });
+
+        let ttl = Value::String("250ms".to_string())
+            .try_deserialize::<HumanDuration>()?;
+        assert_eq!(ttl.as_duration(), Duration::from_millis(250));
+        let roundtrip =
+            Value::try_serialize(&HumanDuration(Duration::from_millis(1500)))?;
+        assert_eq!(roundtrip, Value::String("1s500ms".to_string()));
+        assert_eq!(
+            roundtrip.try_deserialize::<HumanDuration>()?.as_duration(),
+            Duration::from_millis(1500)
+        );
+        assert!(
+            Value::String("soon".to_string())
+                .try_deserialize::<HumanDuration>()
+                .is_err()
+        );
+
+        // Structs named and shaped like `Duration` keep their own format.
+        assert!(
+            Value::String("30s".to_string())
+                .try_deserialize::<Duration>()
+                .is_err()
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_deserialize_option_some() -> anyhow::Result<()> {
         let value = Value::String("test".to_string());
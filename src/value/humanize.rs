@@ -0,0 +1,473 @@
+use std::{
+    fmt::{
+        self,
+        Display,
+        Formatter,
+    },
+    str::FromStr,
+    time::Duration,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+    de,
+};
+
+use crate::{
+    Error,
+    Result,
+    errors::QuantityError,
+};
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The duration units, from the largest, with their length in nanoseconds.
+const DURATION_UNITS: [(&str, u128); 8] = [
+    ("w", 604_800 * NANOS_PER_SEC),
+    ("d", 86_400 * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Parses a humanized duration such as `30s`, `1h30m` or `250ms`.
+///
+/// A duration is a sequence of numbers, each followed by a unit: `ns`, `us`
+/// (or `µs`), `ms`, `s`, `m`, `h`, `d` or `w`. Numbers may have a fractional
+/// part (`1.5h`) and components may be separated by whitespace. A bare number,
+/// such as `42` or `1.5`, is read as seconds.
+pub(crate) fn parse_duration(s: &str) -> Result<Duration> {
+    let error = Error::InvalidDuration;
+    let input = s.trim();
+    if input.is_empty() {
+        return Err(error(QuantityError::Empty));
+    }
+
+    let mut rest = input;
+    let mut total: u128 = 0;
+    while !rest.is_empty() {
+        let (number, tail) = split_number(rest);
+        let (unit, tail) = split_unit(tail);
+        if number.is_empty() {
+            return Err(error(QuantityError::ExpectedNumber));
+        }
+        let unit = match unit {
+            "" if rest == input && tail.is_empty() => "s",
+            "" => return Err(error(QuantityError::MissingUnit)),
+            "µs" => "us",
+            unit => unit,
+        };
+        let Some((_, per_unit)) =
+            DURATION_UNITS.iter().find(|(name, _)| *name == unit)
+        else {
+            return Err(error(QuantityError::UnknownUnit(
+                "ns, us, ms, s, m, h, d or w",
+            )));
+        };
+        let nanos = scale(number, *per_unit).map_err(error)?;
+        total = total
+            .checked_add(nanos)
+            .ok_or(error(QuantityError::Overflow))?;
+        rest = tail.trim_start();
+    }
+
+    let secs = u64::try_from(total / NANOS_PER_SEC)
+        .map_err(|_e| error(QuantityError::Overflow))?;
+    #[allow(clippy::cast_possible_truncation)]
+    let nanos = (total % NANOS_PER_SEC) as u32;
+    Ok(Duration::new(secs, nanos))
+}
+
+/// Parses a humanized byte size such as `512MiB`, `10GB` or `4096`.
+///
+/// Decimal units (`KB`, `MB`, `GB`, `TB`, `PB`, or just `K`, `M`, ...) are
+/// powers of 1000 and binary units (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`) are
+/// powers of 1024. Units are case-insensitive and a bare number is a count of
+/// bytes.
+pub(crate) fn parse_byte_size(s: &str) -> Result<u64> {
+    let error = Error::InvalidByteSize;
+    let (number, tail) = split_number(s.trim());
+    if number.is_empty() {
+        return Err(error(QuantityError::ExpectedNumber));
+    }
+    let unit = tail.trim_start();
+    let multiplier: u128 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1_000,
+        "m" | "mb" => 1_000_000,
+        "g" | "gb" => 1_000_000_000,
+        "t" | "tb" => 1_000_000_000_000,
+        "p" | "pb" => 1_000_000_000_000_000,
+        "ki" | "kib" => 1 << 10,
+        "mi" | "mib" => 1 << 20,
+        "gi" | "gib" => 1 << 30,
+        "ti" | "tib" => 1 << 40,
+        "pi" | "pib" => 1 << 50,
+        _ => {
+            return Err(error(QuantityError::UnknownUnit(
+                "B, KB, MB, GB, TB, PB or KiB to PiB",
+            )));
+        }
+    };
+    let bytes = scale(number, multiplier).map_err(error)?;
+    u64::try_from(bytes).map_err(|_e| error(QuantityError::Overflow))
+}
+
+/// Splits a leading decimal number (digits with an optional fraction) off
+/// `s`.
+fn split_number(s: &str) -> (&str, &str) {
+    let end = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Splits a leading unit (alphabetic characters, including `µ`) off `s`.
+fn split_unit(s: &str) -> (&str, &str) {
+    let s = s.trim_start();
+    let end = s.find(|c: char| !c.is_alphabetic()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Multiplies a decimal number by `unit` exactly, truncating whatever is
+/// left below one.
+fn scale(number: &str, unit: u128) -> std::result::Result<u128, QuantityError> {
+    let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+    if int.is_empty() && frac.is_empty() || frac.contains('.') {
+        return Err(QuantityError::InvalidNumber);
+    }
+    // Digits past the 18th are below a nanosecond of a week, or a byte of a
+    // petabyte.
+    let frac = &frac[..frac.len().min(18)];
+    let int: u128 = if int.is_empty() {
+        0
+    } else {
+        int.parse().map_err(|_e| QuantityError::Overflow)?
+    };
+    let mut value = int.checked_mul(unit).ok_or(QuantityError::Overflow)?;
+    if !frac.is_empty() {
+        #[allow(clippy::cast_possible_truncation)]
+        let digits = frac.len() as u32;
+        let frac: u128 =
+            frac.parse().map_err(|_e| QuantityError::InvalidNumber)?;
+        value = value
+            .checked_add(frac * unit / 10_u128.pow(digits))
+            .ok_or(QuantityError::Overflow)?;
+    }
+    Ok(value)
+}
+
+/// A number of bytes, deserialized from either an integer or a humanized
+/// string such as `"512MiB"` or `"10GB"`.
+///
+/// It displays in the largest unit that represents it exactly.
+///
+/// ```rust
+/// use realme::ByteSize;
+///
+/// let size: ByteSize = "512MiB".parse().expect("parse size");
+/// assert_eq!(size.as_u64(), 512 * 1024 * 1024);
+/// assert_eq!(size.to_string(), "512MiB");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// Returns the number of bytes.
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_byte_size(s).map(Self)
+    }
+}
+
+impl Display for ByteSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const UNITS: [(u64, &str); 10] = [
+            (1 << 50, "PiB"),
+            (1_000_000_000_000_000, "PB"),
+            (1 << 40, "TiB"),
+            (1_000_000_000_000, "TB"),
+            (1 << 30, "GiB"),
+            (1_000_000_000, "GB"),
+            (1 << 20, "MiB"),
+            (1_000_000, "MB"),
+            (1 << 10, "KiB"),
+            (1_000, "KB"),
+        ];
+        if self.0 != 0 {
+            for (size, unit) in UNITS {
+                if self.0.is_multiple_of(size) {
+                    return write!(f, "{}{unit}", self.0 / size);
+                }
+            }
+        }
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        struct ByteSizeVisitor;
+
+        impl de::Visitor<'_> for ByteSizeVisitor {
+            type Value = ByteSize;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("a byte count or a size such as \"512MiB\"")
+            }
+
+            fn visit_u64<E: de::Error>(
+                self,
+                v: u64,
+            ) -> std::result::Result<ByteSize, E> {
+                Ok(ByteSize(v))
+            }
+
+            fn visit_i64<E: de::Error>(
+                self,
+                v: i64,
+            ) -> std::result::Result<ByteSize, E> {
+                u64::try_from(v).map(ByteSize).map_err(de::Error::custom)
+            }
+
+            fn visit_str<E: de::Error>(
+                self,
+                v: &str,
+            ) -> std::result::Result<ByteSize, E> {
+                v.parse().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ByteSizeVisitor)
+    }
+}
+
+/// A `Duration`, deserialized from either a number of seconds or a
+/// humanized string such as `"1h30m"` or `"250ms"`.
+///
+/// Use it for fields that should accept humanized durations; a plain
+/// `Duration` field keeps serde's `{ secs, nanos }` form. It displays and
+/// serializes as a humanized string.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use realme::HumanDuration;
+///
+/// let ttl: HumanDuration = "1h30m".parse().expect("parse duration");
+/// assert_eq!(ttl.as_duration(), Duration::from_secs(90 * 60));
+/// assert_eq!(ttl.to_string(), "1h30m");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    /// Returns the duration.
+    pub const fn as_duration(self) -> Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_duration(s).map(Self)
+    }
+}
+
+impl Display for HumanDuration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
+        for (unit, size) in DURATION_UNITS {
+            if nanos >= size {
+                write!(f, "{}{unit}", nanos / size)?;
+                nanos %= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for HumanDuration {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        struct HumanDurationVisitor;
+
+        impl de::Visitor<'_> for HumanDurationVisitor {
+            type Value = HumanDuration;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str(
+                    "a number of seconds or a duration such as \"1h30m\"",
+                )
+            }
+
+            fn visit_u64<E: de::Error>(
+                self,
+                v: u64,
+            ) -> std::result::Result<HumanDuration, E> {
+                Ok(HumanDuration(Duration::from_secs(v)))
+            }
+
+            fn visit_i64<E: de::Error>(
+                self,
+                v: i64,
+            ) -> std::result::Result<HumanDuration, E> {
+                u64::try_from(v)
+                    .map(|secs| HumanDuration(Duration::from_secs(secs)))
+                    .map_err(de::Error::custom)
+            }
+
+            fn visit_f64<E: de::Error>(
+                self,
+                v: f64,
+            ) -> std::result::Result<HumanDuration, E> {
+                Duration::try_from_secs_f64(v)
+                    .map(HumanDuration)
+                    .map_err(de::Error::custom)
+            }
+
+            fn visit_str<E: de::Error>(
+                self,
+                v: &str,
+            ) -> std::result::Result<HumanDuration, E> {
+                v.parse().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(HumanDurationVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() -> anyhow::Result<()> {
+        assert_eq!(parse_duration("30s")?, Duration::from_secs(30));
+        assert_eq!(parse_duration("1h30m")?, Duration::from_secs(90 * 60));
+        assert_eq!(parse_duration("250ms")?, Duration::from_millis(250));
+        assert_eq!(parse_duration("1h 2m 3s")?, Duration::from_secs(3_723));
+        assert_eq!(parse_duration("1.5h")?, Duration::from_secs(90 * 60));
+        assert_eq!(parse_duration("2d")?, Duration::from_secs(48 * 3600));
+        assert_eq!(parse_duration("10µs")?, Duration::from_micros(10));
+        assert_eq!(parse_duration("42")?, Duration::from_secs(42));
+        assert_eq!(parse_duration("1.5")?, Duration::from_millis(1500));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("1s 5").is_err());
+        for s in ["1..5s", "1.5.3s", ".", ".s"] {
+            assert!(
+                matches!(
+                    parse_duration(s),
+                    Err(Error::InvalidDuration(QuantityError::InvalidNumber))
+                ),
+                "{s}"
+            );
+        }
+        assert!(matches!(
+            parse_duration("99999999999999999999w"),
+            Err(Error::InvalidDuration(QuantityError::Overflow))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_human_duration() -> anyhow::Result<()> {
+        let duration = HumanDuration(Duration::from_millis(5_400_250));
+        assert_eq!(duration.to_string(), "1h30m250ms");
+        assert_eq!(duration.to_string().parse::<HumanDuration>()?, duration);
+        assert_eq!(HumanDuration::default().to_string(), "0s");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_byte_size() -> anyhow::Result<()> {
+        assert_eq!(parse_byte_size("512MiB")?, 512 * 1024 * 1024);
+        assert_eq!(parse_byte_size("10GB")?, 10_000_000_000);
+        assert_eq!(parse_byte_size("4096")?, 4096);
+        assert_eq!(parse_byte_size("1.5 KiB")?, 1536);
+        assert_eq!(parse_byte_size("2k")?, 2000);
+        assert!(parse_byte_size("12 parsecs").is_err());
+        assert!(parse_byte_size("100000PiB").is_err());
+        assert!(matches!(
+            parse_byte_size("1.2.3MB"),
+            Err(Error::InvalidByteSize(QuantityError::InvalidNumber))
+        ));
+        let err = parse_byte_size("5hunter").expect_err("unknown unit");
+        assert!(!err.to_string().contains("hunter"), "{err}");
+        let err = parse_duration("5hunter").expect_err("unknown unit");
+        assert!(!err.to_string().contains("hunter"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_size_display() {
+        assert_eq!(ByteSize(0).to_string(), "0B");
+        assert_eq!(ByteSize(1023).to_string(), "1023B");
+        assert_eq!(ByteSize(512 * 1024 * 1024).to_string(), "512MiB");
+        assert_eq!(ByteSize(10_000_000_000).to_string(), "10GB");
+    }
+}
@@ -1,6 +1,9 @@
 mod access;
 mod cast;
+mod datetime;
 mod des;
+mod humanize;
+mod secret;
 mod ser;
 
 use std::fmt::{
@@ -8,6 +11,28 @@ use std::fmt::{
     Formatter,
 };
 
+pub use access::{
+    Change,
+    MergeConflict,
+    MergeOptions,
+    MergeStrategy,
+    PatchOperation,
+    Query,
+};
+#[cfg(feature = "yaml")]
+pub(crate) use datetime::parse_datetime;
+pub use datetime::{
+    Date,
+    Datetime,
+    Offset,
+    Time,
+};
+pub use humanize::{
+    ByteSize,
+    HumanDuration,
+};
+pub(crate) use secret::REDACTED;
+pub use secret::Secret;
 use ser::ValueSerializer;
 use serde::{
     Deserialize,
@@ -23,14 +48,26 @@ pub type Array = Vec<Value>;
 pub type Table = Map<String, Value>;
 
 /// Representation of a TOML value.
+///
+/// Integers are stored in the smallest variant that holds them, in the order
+/// `Integer`, `UInteger`, `Integer128`, `UInteger128`. Values built with
+/// `Value::from` or through serde always follow this order, so two equal
+/// numbers compare equal.
 #[derive(Default, PartialEq, Clone, Debug)]
 pub enum Value {
     #[default]
     Null,
     Boolean(bool),
     Integer(i64),
+    /// An unsigned integer larger than `i64::MAX`.
+    UInteger(u64),
+    /// An integer that fits in neither `i64` nor `u64`.
+    Integer128(i128),
+    /// An unsigned integer larger than `i128::MAX`.
+    UInteger128(u128),
     Float(f64),
     String(String),
+    Datetime(Datetime),
     Array(Array),
     Table(Table),
 }
@@ -41,8 +78,12 @@ impl Display for Value {
             Self::Null => write!(f, "null"),
             Self::Boolean(b) => write!(f, "{b}"),
             Self::Integer(i) => write!(f, "{i}"),
+            Self::UInteger(u) => write!(f, "{u}"),
+            Self::Integer128(i) => write!(f, "{i}"),
+            Self::UInteger128(u) => write!(f, "{u}"),
             Self::Float(fl) => write!(f, "{fl}"),
             Self::String(s) => write!(f, "{s}"),
+            Self::Datetime(d) => write!(f, "{d}"),
             Self::Array(a) => write!(f, "{a:?}"),
             Self::Table(t) => write!(f, "{t:?}"),
         }
@@ -63,9 +104,13 @@ impl Value {
         match self {
             Self::Null => "null",
             Self::Boolean(_) => "boolean",
-            Self::Integer(_) => "integer",
+            Self::Integer(_) |
+            Self::UInteger(_) |
+            Self::Integer128(_) |
+            Self::UInteger128(_) => "integer",
             Self::Float(_) => "float",
             Self::String(_) => "string",
+            Self::Datetime(_) => "datetime",
             Self::Array(_) => "array",
             Self::Table(_) => "table",
         }
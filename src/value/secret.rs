@@ -0,0 +1,120 @@
+use std::fmt::{
+    self,
+    Debug,
+    Display,
+    Formatter,
+};
+
+use serde::{
+    Deserialize,
+    Deserializer,
+    de,
+};
+
+/// The text shown in place of sensitive values.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// A value that is never shown by `Debug` or `Display`, such as a password or
+/// an API key.
+///
+/// It deserializes like `T`, so it can be used directly in configuration
+/// structs. Call [`Secret::expose`] to read the value.
+///
+/// ```rust
+/// use realme::{
+///     Secret,
+///     Value,
+/// };
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Database {
+///     user:     String,
+///     password: Secret<String>,
+/// }
+///
+/// let mut value = Value::Null;
+/// value
+///     .set("user", Value::String("admin".to_string()))
+///     .expect("set user");
+/// value
+///     .set("password", Value::String("hunter2".to_string()))
+///     .expect("set password");
+/// let db: Database = value.try_deserialize().expect("deserialize");
+///
+/// assert_eq!(db.password.expose(), "hunter2");
+/// assert!(!format!("{db:?}").contains("hunter2"));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    /// Wraps `value`.
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub const fn expose(&self) -> &T {
+        &self.0
+    }
+
+    /// Returns the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({REDACTED})")
+    }
+}
+
+impl<T> Display for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Secret<T> {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        // The error of `T` may quote the value, so it is replaced.
+        T::deserialize(deserializer)
+            .map(Self)
+            .map_err(|_e| de::Error::custom("invalid value for a secret"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+
+    #[test]
+    fn test_secret() -> anyhow::Result<()> {
+        let secret: Secret<String> =
+            Value::String("hunter2".to_string()).try_deserialize()?;
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(format!("{secret:?}"), "Secret([REDACTED])");
+        assert_eq!(secret.to_string(), "[REDACTED]");
+        assert_eq!(secret.into_inner(), "hunter2");
+
+        let port: Secret<u16> = Value::Integer(5432).try_deserialize()?;
+        assert_eq!(*port.expose(), 5432);
+
+        let err = Value::String("hunter2".to_string())
+            .try_deserialize::<Secret<u16>>()
+            .expect_err("not a port");
+        assert!(!err.to_string().contains("hunter2"), "{err}");
+        Ok(())
+    }
+}
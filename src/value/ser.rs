@@ -13,11 +13,25 @@ use serde::{
     },
 };
 
-use super::Value;
+use super::{
+    Value,
+    datetime::DATETIME_NAME,
+};
 use crate::Map;
 
 pub struct ValueSerializer;
 
+/// Turns the string carried by a datetime marker into a `Value::Datetime`.
+fn datetime_from(value: Value) -> Result<Value, crate::errors::SerializeError> {
+    match value {
+        Value::String(s) => s
+            .parse()
+            .map(Value::Datetime)
+            .map_err(|e: crate::Error| ser::Error::custom(e.to_string())),
+        _ => Err(ser::Error::custom("datetime must be a string")),
+    }
+}
+
 /// Represents a generic serialization implementation for `Value`.
 impl Serialize for Value {
     fn serialize<S: Serializer>(
@@ -28,8 +42,12 @@ impl Serialize for Value {
             Self::Null => serializer.serialize_none(),
             Self::Boolean(b) => serializer.serialize_bool(*b),
             Self::Integer(i) => serializer.serialize_i64(*i),
+            Self::UInteger(u) => serializer.serialize_u64(*u),
+            Self::Integer128(i) => serializer.serialize_i128(*i),
+            Self::UInteger128(u) => serializer.serialize_u128(*u),
             Self::Float(f) => serializer.serialize_f64(*f),
             Self::String(s) => serializer.serialize_str(s),
+            Self::Datetime(d) => d.serialize(serializer),
             Self::Array(arr) => {
                 let mut seq = serializer.serialize_seq(Some(arr.len()))?;
                 for value in arr {
@@ -54,8 +72,8 @@ impl Serializer for ValueSerializer {
     type Error = crate::errors::SerializeError;
 
     type SerializeSeq = SeqSerializer;
-    type SerializeTuple = TupleSerializer;
-    type SerializeTupleStruct = TupleStructSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
     type SerializeTupleVariant = TupleVariantSerializer;
     type SerializeMap = MapSerializer;
     type SerializeStruct = StructSerializer;
@@ -94,11 +112,15 @@ impl Serializer for ValueSerializer {
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        if i64::try_from(v).is_ok() {
-            Ok(Value::Integer(v as i64))
-        } else {
-            Err(serde::ser::Error::custom("u64 value convert failed"))
-        }
+        Ok(Value::from(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::from(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::from(v))
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
@@ -140,34 +162,41 @@ impl Serializer for ValueSerializer {
         self,
         _name: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("unit type not supported"))
+        Ok(Value::Null)
     }
 
     fn serialize_unit_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("unit type not supported"))
+        Ok(Value::String(variant.to_string()))
     }
 
     fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
+        let value = value.serialize(self)?;
+        if name == DATETIME_NAME {
+            return datetime_from(value);
+        }
+        Ok(value)
     }
 
+    /// Newtype variants are externally tagged, e.g. `{ variant = value }`.
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
+        let mut map = Map::new();
+        map.insert(variant.to_string(), value.serialize(self)?);
+        Ok(Value::Table(map))
     }
 
     fn serialize_seq(
@@ -181,7 +210,7 @@ impl Serializer for ValueSerializer {
         self,
         _len: usize,
     ) -> Result<Self::SerializeTuple, Self::Error> {
-        Ok(TupleSerializer::new())
+        Ok(SeqSerializer::new())
     }
 
     fn serialize_tuple_struct(
@@ -189,17 +218,17 @@ impl Serializer for ValueSerializer {
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        Ok(TupleStructSerializer::new())
+        Ok(SeqSerializer::new())
     }
 
     fn serialize_tuple_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Ok(TupleVariantSerializer::new())
+        Ok(TupleVariantSerializer::new(variant))
     }
 
     fn serialize_map(
@@ -221,10 +250,10 @@ impl Serializer for ValueSerializer {
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Ok(StructVariantSerializer::new())
+        Ok(StructVariantSerializer::new(variant))
     }
 }
 
@@ -243,28 +272,20 @@ impl SeqSerializer {
     }
 }
 
-/// Serializer for tuples, currently not supported.
-pub struct TupleSerializer;
-impl TupleSerializer {
-    /// Constructs a new `TupleSerializer`.
-    const fn new() -> Self {
-        Self {}
-    }
-}
-/// Serializer for tuple structs, currently not supported.
-pub struct TupleStructSerializer;
-impl TupleStructSerializer {
-    /// Constructs a new `TupleStructSerializer`.
-    const fn new() -> Self {
-        Self {}
-    }
+/// Serializer for tuple variants, storing elements as
+/// `{ variant = [elements] }`.
+pub struct TupleVariantSerializer {
+    variant:  &'static str,
+    elements: Vec<Value>,
 }
-/// Serializer for tuple variants, currently not supported.
-pub struct TupleVariantSerializer;
+
 impl TupleVariantSerializer {
     /// Constructs a new `TupleVariantSerializer`.
-    const fn new() -> Self {
-        Self {}
+    const fn new(variant: &'static str) -> Self {
+        Self {
+            variant,
+            elements: Vec::new(),
+        }
     }
 }
 /// Serializer for maps, converting key-value pairs into `Value::Table`.
@@ -296,13 +317,20 @@ impl StructSerializer {
         }
     }
 }
-/// Serializer for struct variants, currently not supported.
-pub struct StructVariantSerializer;
+/// Serializer for struct variants, storing fields as
+/// `{ variant = { fields } }`.
+pub struct StructVariantSerializer {
+    variant: &'static str,
+    fields:  Map<String, Value>,
+}
 
 impl StructVariantSerializer {
     /// Constructs a new `StructVariantSerializer`.
-    const fn new() -> Self {
-        Self {}
+    fn new(variant: &'static str) -> Self {
+        Self {
+            variant,
+            fields: Map::new(),
+        }
     }
 }
 
@@ -324,35 +352,35 @@ impl SerializeSeq for SeqSerializer {
     }
 }
 
-impl SerializeTuple for TupleSerializer {
+impl SerializeTuple for SeqSerializer {
     type Ok = Value;
     type Error = crate::errors::SerializeError;
 
     fn serialize_element<T: ?Sized + Serialize>(
         &mut self,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        Err(ser::Error::custom("tuple type not supported"))
+        SerializeSeq::serialize_element(self, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("tuple type not supported"))
+        SerializeSeq::end(self)
     }
 }
 
-impl SerializeTupleStruct for TupleStructSerializer {
+impl SerializeTupleStruct for SeqSerializer {
     type Ok = Value;
     type Error = crate::errors::SerializeError;
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        Err(ser::Error::custom("tuple struct type not supported"))
+        SerializeSeq::serialize_element(self, value)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("tuple struct type not supported"))
+        SerializeSeq::end(self)
     }
 }
 
@@ -362,13 +390,16 @@ impl SerializeTupleVariant for TupleVariantSerializer {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _value: &T,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        Err(ser::Error::custom("tuple variant type not supported"))
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("tuple variant type not supported"))
+        let mut map = Map::new();
+        map.insert(self.variant.to_string(), Value::Array(self.elements));
+        Ok(Value::Table(map))
     }
 }
 
@@ -426,7 +457,7 @@ impl SerializeStruct for StructSerializer {
         // "toml" feature is enabled.
         #[cfg(feature = "toml")]
         if key == "$__toml_private_datetime" {
-            self.fields = serialized_value;
+            self.fields = datetime_from(serialized_value)?;
             return Ok(());
         }
 
@@ -450,7 +481,11 @@ impl SerializeStruct for StructSerializer {
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(self.fields)
+        // A struct without fields is still a table, not a missing value
+        match self.fields {
+            Value::Null => Ok(Value::Table(Map::new())),
+            fields => Ok(fields),
+        }
     }
 }
 
@@ -460,14 +495,18 @@ impl SerializeStructVariant for StructVariantSerializer {
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _key: &'static str,
-        _value: &T,
+        key: &'static str,
+        value: &T,
     ) -> Result<(), Self::Error> {
-        Err(ser::Error::custom("struct variant type not supported"))
+        self.fields
+            .insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Err(ser::Error::custom("struct variant type not supported"))
+        let mut map = Map::new();
+        map.insert(self.variant.to_string(), Value::Table(self.fields));
+        Ok(Value::Table(map))
     }
 }
 
@@ -522,4 +561,133 @@ mod test {
         assert_eq!(val, output);
         Ok(())
     }
+
+    #[test]
+    fn test_large_integers() -> anyhow::Result<()> {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Ids {
+            small: u64,
+            mask:  u64,
+            wide:  i128,
+            huge:  u128,
+        }
+
+        let ids = Ids {
+            small: 7,
+            mask:  u64::MAX,
+            wide:  i128::MIN,
+            huge:  u128::MAX,
+        };
+        let value = Value::try_serialize(&ids)?;
+        assert_eq!(value.get("small"), Some(&Value::Integer(7)));
+        assert_eq!(value.get("mask"), Some(&Value::UInteger(u64::MAX)));
+        assert_eq!(value.get("wide"), Some(&Value::Integer128(i128::MIN)));
+        assert_eq!(value.get("huge"), Some(&Value::UInteger128(u128::MAX)));
+        assert_eq!(value.try_deserialize::<Ids>()?, ids);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_and_unit() -> anyhow::Result<()> {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Unit;
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Pair(i32, String);
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        struct Test {
+            unit:  (),
+            empty: Unit,
+            tuple: (u8, bool),
+            pair:  Pair,
+        }
+
+        let test = Test {
+            unit:  (),
+            empty: Unit,
+            tuple: (1, true),
+            pair:  Pair(-1, "a".to_string()),
+        };
+        let config = Value::try_serialize(&test)?;
+        assert_eq!(
+            config.get("pair"),
+            Some(&Value::Array(vec![
+                Value::Integer(-1),
+                Value::String("a".to_string())
+            ]))
+        );
+        let actual: Test = config.try_deserialize()?;
+        assert_eq!(test, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_externally_tagged_enum() -> anyhow::Result<()> {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[serde(rename_all = "snake_case")]
+        enum Backend {
+            Memory,
+            Local(std::path::PathBuf),
+            Remote(String, u16),
+            S3 { bucket: String },
+        }
+
+        let backends = vec![
+            Backend::Memory,
+            Backend::Local("/tmp/data".into()),
+            Backend::Remote("localhost".to_string(), 9000),
+            Backend::S3 {
+                bucket: "assets".to_string(),
+            },
+        ];
+        let config = Value::try_serialize(&backends)?;
+        assert_eq!(config.get(0), Some(&Value::String("memory".to_string())));
+        assert_eq!(
+            config.get(3).and_then(|v| v.get("s3.bucket")),
+            Some(&Value::String("assets".to_string()))
+        );
+        let actual: Vec<Backend> = config.try_deserialize()?;
+        assert_eq!(backends, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_internally_and_adjacently_tagged_enum() -> anyhow::Result<()> {
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[serde(tag = "type", rename_all = "snake_case")]
+        enum Internal {
+            Memory,
+            S3 { bucket: String },
+        }
+
+        #[derive(Debug, Serialize, Deserialize, PartialEq)]
+        #[serde(tag = "t", content = "c", rename_all = "snake_case")]
+        enum Adjacent {
+            Memory,
+            Local(String),
+            S3 { bucket: String },
+        }
+
+        let internal = vec![Internal::Memory, Internal::S3 {
+            bucket: "assets".to_string(),
+        }];
+        let config = Value::try_serialize(&internal)?;
+        assert_eq!(
+            config.get(1).and_then(|v| v.get("type")),
+            Some(&Value::String("s3".to_string()))
+        );
+        let actual: Vec<Internal> = config.try_deserialize()?;
+        assert_eq!(internal, actual);
+
+        let adjacent = vec![
+            Adjacent::Memory,
+            Adjacent::Local("/tmp".to_string()),
+            Adjacent::S3 {
+                bucket: "assets".to_string(),
+            },
+        ];
+        let config = Value::try_serialize(&adjacent)?;
+        let actual: Vec<Adjacent> = config.try_deserialize()?;
+        assert_eq!(adjacent, actual);
+        Ok(())
+    }
 }
@@ -181,3 +181,17 @@ fn json_parse() -> anyhow::Result<()> {
     assert_eq!(config, expected);
     Ok(())
 }
+
+#[test]
+fn json_large_unsigned() -> anyhow::Result<()> {
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<JsonParser>::new(
+            r#"{"id": 18446744073709551615, "mask": 9223372036854775808}"#,
+        )))
+        .build()?;
+
+    assert_eq!(realme.get("id"), Some(&Value::UInteger(u64::MAX)));
+    assert_eq!(realme.get_as::<u64, _>("mask"), Some(1 << 63));
+    assert!(realme.get_as::<i64, _>("mask").is_none());
+    Ok(())
+}
@@ -174,3 +174,137 @@ fn toml_parse() -> anyhow::Result<()> {
     assert_eq!(my_config, expected);
     Ok(())
 }
+
+#[test]
+fn toml_datetime() -> anyhow::Result<()> {
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<TomlParser>::new(
+            "
+            dob = 1979-05-27T07:32:00.999999-07:00
+            local = 1979-05-27T07:32:00
+            day = 1979-05-27
+            at = 07:32:00
+            ",
+        )))
+        .build()?;
+
+    assert!(matches!(realme.get("dob"), Some(Value::Datetime(_))));
+    assert_eq!(
+        realme.get_as::<String, _>("dob").expect("dob"),
+        "1979-05-27T07:32:00.999999-07:00"
+    );
+    let dob: chrono::DateTime<chrono::Utc> = realme.get_as("dob").expect("dob");
+    assert_eq!(dob.to_rfc3339(), "1979-05-27T14:32:00.999999+00:00");
+    let local: chrono::NaiveDateTime = realme.get_as("local").expect("local");
+    assert_eq!(local.to_string(), "1979-05-27 07:32:00");
+    let day: chrono::NaiveDate = realme.get_as("day").expect("day");
+    assert_eq!(day.to_string(), "1979-05-27");
+    let at: chrono::NaiveTime = realme.get_as("at").expect("at");
+    assert_eq!(at.to_string(), "07:32:00");
+    Ok(())
+}
+
+#[test]
+fn toml_humanized() -> anyhow::Result<()> {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        timeout:   HumanDuration,
+        body_size: ByteSize,
+    }
+
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<TomlParser>::new(
+            r#"
+            [server]
+            timeout = "1m30s"
+            body_size = "10MB"
+            [cache]
+            ttl = "250ms"
+            size = "512MiB"
+            "#,
+        )))
+        .build()?;
+
+    assert_eq!(
+        realme.get_duration("cache.ttl"),
+        Some(std::time::Duration::from_millis(250))
+    );
+    assert_eq!(realme.get_bytes("cache.size"), Some(512 * 1024 * 1024));
+    assert_eq!(
+        realme.get_as::<Server, _>("server"),
+        Some(Server {
+            timeout:   HumanDuration(std::time::Duration::from_secs(90)),
+            body_size: ByteSize(10_000_000),
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn toml_quoted_keys() -> anyhow::Result<()> {
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<TomlParser>::new(
+            r#"
+            [hosts."example.com"]
+            port = 443
+            [hosts."api.example.com"]
+            port = 8443
+            "#,
+        )))
+        .build()?;
+
+    assert_eq!(
+        realme.get_as::<u16, _>(r#"hosts."example.com".port"#),
+        Some(443)
+    );
+    assert_eq!(
+        realme.get_as::<u16, _>(r#"hosts["api.example.com"].port"#),
+        Some(8443)
+    );
+    assert_eq!(realme.keys(), vec![
+        r#"hosts."api.example.com".port"#,
+        r#"hosts."example.com".port"#,
+    ]);
+    Ok(())
+}
+
+#[test]
+fn toml_parse_error_location() -> anyhow::Result<()> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    std::io::Write::write_all(
+        &mut file,
+        b"password = \"hunter2\"\nport = = 8080\n",
+    )?;
+    let error = Realme::builder()
+        .load(Adaptor::new(FileSource::<TomlParser>::new(file.path())))
+        .build()
+        .expect_err("invalid toml");
+
+    let message = error.to_string();
+    assert!(message.contains(&file.path().display().to_string()));
+    assert!(message.contains("line 2, column 8: `port = = 8080`"));
+    assert!(!message.contains("hunter2"));
+    let realme::Error::AdaptorFailed { source, .. } = &error else {
+        panic!("expected an adaptor failure, got {error:?}");
+    };
+    let realme::Error::ParseError(parse) = source.as_ref() else {
+        panic!("expected a parse error, got {source:?}");
+    };
+    let location = parse.location.as_ref().expect("location");
+    assert_eq!((location.line, location.column), (2, 8));
+    assert!(format!("{parse:#}").ends_with("2 | port = = 8080\n  |        ^"));
+
+    let error = Realme::builder()
+        .load(Adaptor::new(StringSource::<TomlParser>::new(
+            "password = \"hunter2\"\nport = = 8080\n",
+        )))
+        .build()
+        .expect_err("invalid toml");
+    assert!(
+        error
+            .to_string()
+            .starts_with("Failed to load inline string #")
+    );
+    assert!(!error.to_string().contains("hunter2"));
+    Ok(())
+}
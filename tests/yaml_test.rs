@@ -176,3 +176,108 @@ fn yaml_parse() -> anyhow::Result<()> {
     assert_eq!(config, expected);
     Ok(())
 }
+
+#[test]
+fn yaml_timestamp() -> anyhow::Result<()> {
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<YamlParser>::new(
+            "dob: 2001-12-14t21:59:43.10-05:00
+day: 2002-12-14
+times: [2001-12-14 21:59:43.10Z]
+name: not a timestamp
+",
+        )))
+        .build()?;
+
+    assert!(matches!(realme.get("dob"), Some(Value::Datetime(_))));
+    assert!(matches!(realme.get("times[0]"), Some(Value::Datetime(_))));
+    assert!(matches!(realme.get("name"), Some(Value::String(_))));
+    let dob: chrono::DateTime<chrono::Utc> = realme.get_as("dob").expect("dob");
+    assert_eq!(dob.to_rfc3339(), "2001-12-15T02:59:43.100+00:00");
+    let day: chrono::NaiveDate = realme.get_as("day").expect("day");
+    assert_eq!(day.to_string(), "2002-12-14");
+    Ok(())
+}
+
+#[test]
+fn yaml_quoted_timestamp_stays_string() -> anyhow::Result<()> {
+    let realme = Realme::builder()
+        .load(Adaptor::new(StringSource::<YamlParser>::new(
+            r#"version: "2024-01-01"
+release: '2001-12-14 21:59:43.10Z'
+tagged: !!str 2024-01-01
+dates:
+  - 2024-01-01
+  - "2024-01-01"
+"#,
+        )))
+        .build()?;
+
+    assert_eq!(
+        realme.get("version"),
+        Some(&Value::String("2024-01-01".to_string()))
+    );
+    assert_eq!(
+        realme.get_as::<String, _>("release").expect("release"),
+        "2001-12-14 21:59:43.10Z"
+    );
+    assert!(matches!(realme.get("tagged"), Some(Value::String(_))));
+    assert!(matches!(realme.get("dates[0]"), Some(Value::Datetime(_))));
+    assert!(matches!(realme.get("dates[1]"), Some(Value::String(_))));
+    Ok(())
+}
+
+#[test]
+fn yaml_parse_error_location() {
+    let error = Realme::builder()
+        .load(Adaptor::new(StringSource::<YamlParser>::new(
+            "password: hunter2\nports: [1, 2\nhost: localhost\n",
+        )))
+        .build()
+        .expect_err("invalid yaml");
+
+    let message = error.to_string();
+    assert!(message.contains("at line 3"), "{message}");
+    assert!(!message.contains("hunter2"));
+    assert!(!message.contains("Index:"));
+}
+
+#[test]
+fn yaml_parse_value() -> anyhow::Result<()> {
+    let value = YamlParser::parse_value(
+        "base: &base
+  port: 8080
+  ratio: .5
+  empty: ~
+  hex: 0x1f
+server:
+  <<: *base
+  copy: *base
+  count: !!int 3
+  flag: !!str true
+  1: one
+",
+    )?;
+    assert_eq!(value.get("base.port"), Some(&Value::Integer(8080)));
+    assert_eq!(value.get("base.ratio"), Some(&Value::Float(0.5)));
+    assert_eq!(value.get("base.empty"), Some(&Value::Null));
+    assert_eq!(value.get("base.hex"), Some(&Value::Integer(31)));
+    assert_eq!(value.get("server.copy"), value.get("base"));
+    assert_eq!(value.get("server.count"), Some(&Value::Integer(3)));
+    assert_eq!(
+        value.get("server.flag"),
+        Some(&Value::String("true".to_string()))
+    );
+    assert_eq!(
+        value.get("server[\"1\"]"),
+        Some(&Value::String("one".to_string()))
+    );
+
+    let error = YamlParser::parse_value("count: !!int three\n")
+        .expect_err("invalid int");
+    assert!(error.to_string().contains("at line 1"), "{error}");
+    let error =
+        YamlParser::parse_value("? [a, b]\n: c\n").expect_err("sequence key");
+    assert!(error.to_string().contains("scalar keys"), "{error}");
+    Ok(())
+}